@@ -0,0 +1,100 @@
+//! Capture the rawkuma pages the regression tests parse into `test/fixtures`.
+//!
+//! Scripts, styles, iframes, ads and comments are stripped, keeping the
+//! `ts_reader.run` script the chapter parser reads the images from.
+//! `challenge.html` is not captured, the site only serves it when it blocks a client.
+//!
+//! Run `cargo run --example capture_fixtures [name...]`, then review the new
+//! outputs with `cargo insta review`.
+
+use std::path::Path;
+
+use scraper::{Html, Node, Selector};
+
+const PAGES: &[(&str, &str)] = &[
+    ("home", "https://rawkuma.com/"),
+    ("search", "https://rawkuma.com/page/2/?s=kanojo"),
+    (
+        "search_empty",
+        "https://rawkuma.com/?s=nothing-matches-this",
+    ),
+    (
+        "manga_list",
+        "https://rawkuma.com/manga/?page=2&order=update",
+    ),
+    (
+        "manga_details",
+        "https://rawkuma.com/manga/kanojo-okarishimasu/",
+    ),
+    ("oneshot", "https://rawkuma.com/manga/hoshi-no-oto/"),
+    (
+        "chapter",
+        "https://rawkuma.com/kanojo-okarishimasu-chapter-321/",
+    ),
+];
+
+/// Elements removed from the captures
+const STRIPPED: &str = "style, link, iframe, ins, .kln, .blox, [class*=\"ads\"], [id*=\"ads\"]";
+
+/// `html` without its scripts, styles, ads and comments
+fn trim(html: &str) -> String {
+    let mut document = Html::parse_document(html);
+    let stripped = Selector::parse(STRIPPED).unwrap();
+    let scripts = Selector::parse("script").unwrap();
+    let mut removed: Vec<_> = document
+        .select(&stripped)
+        .map(|element| element.id())
+        .collect();
+    removed.extend(
+        document
+            .select(&scripts)
+            .filter(|script| !script.inner_html().contains("ts_reader.run"))
+            .map(|script| script.id()),
+    );
+    removed.extend(
+        document
+            .tree
+            .nodes()
+            .filter(|node| matches!(node.value(), Node::Comment(_)))
+            .map(|node| node.id()),
+    );
+    for id in removed {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+    let html = document.root_element().html();
+    let lines: Vec<&str> = html
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    format!("<!DOCTYPE html>\n{}\n", lines.join("\n"))
+}
+
+#[tokio::main]
+async fn main() {
+    let names: Vec<String> = std::env::args().skip(1).collect();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("test/fixtures");
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0")
+        .build()
+        .unwrap();
+    for (name, url) in PAGES {
+        if !names.is_empty() && !names.iter().any(|n| n == name) {
+            continue;
+        }
+        let html = client
+            .get(*url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let path = fixtures.join(format!("{}.html", name));
+        std::fs::write(&path, trim(&html)).unwrap();
+        println!("{} -> {}", url, path.display());
+    }
+}
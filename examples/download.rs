@@ -1,13 +1,26 @@
-use reqwest::Client;
+use std::sync::Arc;
+
+use rawkuma_scraper::{download::ChapterDownloaderBuilder, RawKumaClient};
 
 /// we use this `rouhou-ore-no-iinazuke-ni-natta-jimiko-ie-de-wa-kawaii-shika-nai-chapter-14/` for testing
 #[tokio::main]
 async fn main() {
-    let request_client = Client::new();
-    let mut client = rawkuma_scraper::RawKumaClient::new(request_client);
-    let chapter = client
-        .chapter(&"rouhou-ore-no-iinazuke-ni-natta-jimiko-ie-de-wa-kawaii-shika-nai-chapter-21-1")
+    let downloader = ChapterDownloaderBuilder::default()
+        .client(RawKumaClient::default())
+        .on_progress(Arc::new(|progress| {
+            println!(
+                "{}/{} {}",
+                progress.downloaded, progress.total, progress.url
+            )
+        }))
+        .build()
+        .unwrap();
+    let files = downloader
+        .download_slug(
+            "rouhou-ore-no-iinazuke-ni-natta-jimiko-ie-de-wa-kawaii-shika-nai-chapter-21-1",
+            "./chapter-21-1",
+        )
         .await
         .unwrap();
-    println!("{}", serde_json::to_string_pretty(&chapter).unwrap());
+    println!("{} pages downloaded", files.len());
 }
@@ -1,14 +1,24 @@
-use rawkuma_scraper::types::{manga::MangaListParameter, ToUrlParam};
-use reqwest::Url;
+use jsonxf::pretty_print;
+use rawkuma_scraper::{
+    enums::manga::{Order, Status},
+    types::manga::MangaListParameterBuilder,
+    RawKumaClient,
+};
 
 #[tokio::main]
 async fn main() {
+    let client = RawKumaClient::default();
+    let params = MangaListParameterBuilder::default()
+        .page(1)
+        .status(Status::Ongoing)
+        .genre(Vec::new())
+        .order(Order::Update)
+        .type_(Default::default())
+        .build()
+        .unwrap();
+    let list = client.manga_list(&params).await.unwrap();
     println!(
         "{}",
-        Url::parse_with_params(
-            "https://rawkuma.com",
-            MangaListParameter::default().to_url_param()
-        )
-        .unwrap()
+        pretty_print(serde_json::to_string(&(list)).unwrap().as_str()).unwrap()
     );
 }
@@ -1,12 +1,16 @@
+use futures::StreamExt;
 use jsonxf::pretty_print;
 use rawkuma_scraper::RawKumaClient;
 
 #[tokio::main]
 async fn main() {
-    let mut client = RawKumaClient::default();
-    let home = client.search(&"konsei").await.unwrap();
-    println!(
-        "{}",
-        pretty_print(serde_json::to_string(&(home)).unwrap().as_str()).unwrap()
-    );
+    let client = RawKumaClient::default();
+    let mut pages = Box::pin(client.search_stream(&"konsei").unwrap());
+    while let Some(page) = pages.next().await {
+        let page = page.unwrap();
+        println!(
+            "{}",
+            pretty_print(serde_json::to_string(&(page)).unwrap().as_str()).unwrap()
+        );
+    }
 }
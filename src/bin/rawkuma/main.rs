@@ -0,0 +1,500 @@
+use std::{
+    io::{self, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use rawkuma_scraper::{
+    download::{ChapterDownloader, ChapterDownloaderBuilder},
+    enums::manga::{Genre, Order, Status, Type},
+    export::{cbz::CbzExporter, epub::EpubExporter},
+    types::{
+        manga::{MangaListParameterBuilder, RawKumaMangaDetailData},
+        BsxTitleData, Chapter, ParseMode, RawKumaResult,
+    },
+    RawKumaClient, Source, SourceConfig,
+};
+use serde::Serialize;
+
+mod table;
+
+use table::Table;
+
+/// Browse and download from rawkuma, or a sibling site given its source config.
+#[derive(Parser)]
+#[command(name = "rawkuma", version)]
+struct Cli {
+    /// Print JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+    /// `.json` or `.toml` source config of another site, see `SourceConfig`
+    #[arg(long, global = true, value_name = "FILE")]
+    source: Option<PathBuf>,
+    /// Fail on the first item that cannot be parsed, instead of skipping it with a warning
+    #[arg(long, global = true)]
+    strict: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Cli {
+    /// Lenient like the library, unless `--strict` is given
+    fn get_parse_mode(&self) -> ParseMode {
+        if self.strict {
+            ParseMode::Strict
+        } else {
+            ParseMode::Lenient
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Popular series and latest updates
+    Home,
+    /// Search series by title
+    Search {
+        query: String,
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+    },
+    /// Browse the series list
+    List {
+        /// Genre slug such as `action`, can be repeated
+        #[arg(long, value_parser = known::<Genre>)]
+        genre: Vec<Genre>,
+        /// `ongoing`, `completed` or `hiatus`
+        #[arg(long, value_parser = known::<Status>)]
+        status: Option<Status>,
+        /// `manga`, `manhwa`, `manhua`, `comic` or `novel`
+        #[arg(long = "type", value_parser = known::<Type>)]
+        type_: Option<Type>,
+        /// `title`, `titlereverse`, `update`, `latest` or `popular`
+        #[arg(long, value_parser = known::<Order>)]
+        order: Option<Order>,
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+    },
+    /// Details of a series
+    Info {
+        /// Slug or url of the series
+        manga: String,
+    },
+    /// Chapters of a series
+    Chapters {
+        /// Slug or url of the series
+        manga: String,
+    },
+    /// Reader page of a chapter and its images
+    Chapter {
+        /// Slug or url of the chapter
+        chapter: String,
+    },
+    /// Download chapters of a series
+    Download {
+        /// Slug or url of the series
+        manga: String,
+        /// Chapter numbers such as `1-10`, `5`, `12-` or `-3`, every chapter by default
+        #[arg(long, value_parser = parse_range)]
+        range: Option<RangeInclusive<f32>>,
+        #[arg(long, value_enum, default_value_t = Format::Cbz)]
+        format: Format,
+        /// Directory the files are written in
+        #[arg(long, short, default_value = ".")]
+        output: PathBuf,
+        /// Maximum number of pages downloaded at the same time
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// A `.cbz` archive per chapter
+    Cbz,
+    /// A directory of images per chapter
+    Dir,
+    /// A single `.epub` of the chapters
+    Epub,
+}
+
+/// Parse a filter, rejecting the empty values and those the site does not know
+fn known<T>(value: &str) -> Result<T, String>
+where
+    T: for<'a> From<&'a str> + AsRef<str>,
+{
+    if value.trim().is_empty() {
+        return Err("the value is empty".to_string());
+    }
+    let parsed = T::from(value);
+    match parsed.as_ref() == value {
+        true => Ok(parsed),
+        false => Err(format!("unknown value '{}'", value)),
+    }
+}
+
+/// Parse `5`, `1-10`, or an open range such as `12-` or `-3`
+fn parse_range(value: &str) -> Result<RangeInclusive<f32>, String> {
+    let number = |number: &str| {
+        let number = number.trim();
+        f32::from_str(number).map_err(|_| format!("invalid chapter number '{}'", number))
+    };
+    let bound = |bound: &str, default: f32| match bound.trim() {
+        "" => Ok(default),
+        bound => number(bound),
+    };
+    match value.split_once('-') {
+        Some((start, end)) => Ok(bound(start, 0.0)?..=bound(end, f32::MAX)?),
+        None => {
+            let number = number(value)?;
+            Ok(number..=number)
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> RawKumaResult<()> {
+    let mut client = RawKumaClient::default();
+    if let Some(path) = &cli.source {
+        client = client.with_source(SourceConfig::from_path(path)?);
+    }
+    client = client
+        .with_parse_mode(cli.get_parse_mode())
+        .with_parse_warning_handler(Arc::new(|warning| eprintln!("warning: {}", warning)));
+    let json = cli.json;
+    match cli.command {
+        Command::Home => {
+            let home = client.home().await?;
+            if json {
+                return print_json(&home);
+            }
+            writeln!(io::stdout().lock(), "Popular")?;
+            print_titles(&home.popular_title)?;
+            writeln!(io::stdout().lock(), "\nLatest updates")?;
+            let mut table = Table::new(&["Title", "Latest chapter", "Url"]);
+            for title in &home.latest_update {
+                let latest = title.chapters.first().map(|chapter| chapter.text.clone());
+                table.row(vec![
+                    title.title.clone(),
+                    latest.unwrap_or_default(),
+                    title.url.to_string(),
+                ]);
+            }
+            table.print()?;
+        }
+        Command::Search { query, page } => {
+            let search = client.search(&query, page).await?;
+            if json {
+                return print_json(&search);
+            }
+            print_titles(&search.result)?;
+            print_page(
+                search.pagination.current_page,
+                search.pagination.total_pages,
+            )?;
+        }
+        Command::List {
+            genre,
+            status,
+            type_,
+            order,
+            page,
+        } => {
+            let params = MangaListParameterBuilder::default()
+                .page(page)
+                .status(status.unwrap_or_default())
+                .genre(genre)
+                .order(order.unwrap_or_default())
+                .type_(type_.unwrap_or_default())
+                .build()?;
+            let list = client.manga_list(&params).await?;
+            if json {
+                return print_json(&list);
+            }
+            print_titles(&list.result)?;
+            print_page(list.pagination.current_page, list.pagination.total_pages)?;
+        }
+        Command::Info { manga } => {
+            let details = Source::manga_details(&client, &manga).await?;
+            if json {
+                return print_json(&details);
+            }
+            print_details(&details)?;
+        }
+        Command::Chapters { manga } => {
+            let details = Source::manga_details(&client, &manga).await?;
+            if json {
+                return print_json(&details.chapterlist);
+            }
+            let mut table = Table::new(&["Chapter", "Date", "Url"]);
+            for chapter in &details.chapterlist.chapters {
+                table.row(vec![
+                    chapter.number.to_string(),
+                    chapter.chapter_date.clone(),
+                    chapter.url.to_string(),
+                ]);
+            }
+            table.print()?;
+        }
+        Command::Chapter { chapter } => {
+            let data = Source::chapter(&client, &chapter).await?;
+            if json {
+                return print_json(&data);
+            }
+            writeln!(io::stdout().lock(), "{}", data.title)?;
+            let mut table = Table::new(&["Page", "Url"]);
+            for (index, url) in ChapterDownloader::get_images(&data)?.iter().enumerate() {
+                table.row(vec![(index + 1).to_string(), url.to_string()]);
+            }
+            table.print()?;
+        }
+        Command::Download {
+            manga,
+            range,
+            format,
+            output,
+            concurrency,
+        } => {
+            let fetched_at = Utc::now();
+            let details = Source::manga_details(&client, &manga).await?;
+            let downloader = ChapterDownloaderBuilder::default()
+                .client(client)
+                .concurrency(concurrency)
+                .build()?;
+            let range = range.unwrap_or(0.0..=f32::MAX);
+            let files = download(&downloader, &details, fetched_at, range, format, &output).await?;
+            if json {
+                return print_json(&files);
+            }
+            let mut out = io::stdout().lock();
+            for file in files {
+                writeln!(out, "{}", file.display())?;
+            }
+        }
+    }
+    RawKumaResult::Ok(())
+}
+
+/// Write the chapters of `range` in `output`, returning the written files or directories
+async fn download(
+    downloader: &ChapterDownloader,
+    details: &RawKumaMangaDetailData,
+    fetched_at: DateTime<Utc>,
+    range: RangeInclusive<f32>,
+    format: Format,
+    output: &Path,
+) -> RawKumaResult<Vec<PathBuf>> {
+    tokio::fs::create_dir_all(output).await?;
+    let chapters = details.chapterlist.get_range(range.clone());
+    if let Format::Epub = format {
+        let path = output.join(format!("{}.epub", file_stem(&details.data.title)));
+        eprintln!("Downloading {} chapters", chapters.len());
+        EpubExporter::new(downloader.clone())
+            .export(details, range, &path)
+            .await?;
+        return RawKumaResult::Ok(vec![path]);
+    }
+    let mut files: Vec<PathBuf> = Vec::new();
+    for chapter in &chapters {
+        eprintln!("Downloading {}", chapter.number);
+        let name = chapter_file_stem(chapter);
+        let path = match format {
+            Format::Cbz => {
+                let path = output.join(format!("{}.cbz", name));
+                CbzExporter::new(downloader.clone())
+                    .with_reference_time(fetched_at)
+                    .export(&details.data, chapter, &path)
+                    .await?;
+                path
+            }
+            _ => {
+                let path = output.join(name);
+                let chapter_data = downloader.fetch_chapter(&chapter.url).await?;
+                downloader
+                    .download(&chapter_data, &chapter.url, &path)
+                    .await?;
+                path
+            }
+        };
+        files.push(path);
+    }
+    RawKumaResult::Ok(files)
+}
+
+fn chapter_file_stem(chapter: &Chapter) -> String {
+    match chapter.slug() {
+        Ok(slug) => slug.as_str().to_string(),
+        Err(_) => file_stem(&chapter.number.to_string()),
+    }
+}
+
+/// `name` without the characters file systems reject
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Print `value`, returning the write error such as a closed pipe instead of panicking
+fn print_json(value: &impl Serialize) -> RawKumaResult<()> {
+    let json = serde_json::to_string_pretty(value)?;
+    writeln!(io::stdout().lock(), "{}", json)?;
+    RawKumaResult::Ok(())
+}
+
+fn print_titles(titles: &[BsxTitleData]) -> RawKumaResult<()> {
+    let mut table = Table::new(&["Title", "Rating", "Url"]);
+    for title in titles {
+        table.row(vec![
+            title.title.clone(),
+            format!("{:.2}", title.rating),
+            title.url.to_string(),
+        ]);
+    }
+    RawKumaResult::Ok(table.print()?)
+}
+
+fn print_page(current: u32, total: Option<u32>) -> RawKumaResult<()> {
+    let mut out = io::stdout().lock();
+    match total {
+        Some(total) => writeln!(out, "\nPage {}/{}", current, total)?,
+        None => writeln!(out, "\nPage {}", current)?,
+    }
+    RawKumaResult::Ok(())
+}
+
+fn print_details(details: &RawKumaMangaDetailData) -> RawKumaResult<()> {
+    let data = &details.data;
+    let genres: Vec<&str> = data
+        .genres
+        .iter()
+        .map(|genre| genre.name.as_str())
+        .collect();
+    let mut table = Table::new(&["Field", "Value"]);
+    table
+        .row(vec!["Title".to_string(), data.name.clone()])
+        .row(vec![
+            "Alternative titles".to_string(),
+            data.alternative_titles.join(", "),
+        ])
+        .row(vec![
+            "Author".to_string(),
+            data.get_credited_author().to_string(),
+        ])
+        .row(vec![
+            "Artist".to_string(),
+            data.artist.clone().unwrap_or_default(),
+        ])
+        .row(vec![
+            "Status".to_string(),
+            data.status
+                .as_ref()
+                .map(|status| status.as_str().to_string())
+                .unwrap_or_default(),
+        ])
+        .row(vec![
+            "Type".to_string(),
+            data.type_
+                .as_ref()
+                .map(|type_| type_.as_str().to_string())
+                .unwrap_or_default(),
+        ])
+        .row(vec!["Genres".to_string(), genres.join(", ")])
+        .row(vec![
+            "Rating".to_string(),
+            format!("{} ({} votes)", data.rating_value, data.rating_count),
+        ])
+        .row(vec![
+            "Chapters".to_string(),
+            details.chapterlist.chapters.len().to_string(),
+        ])
+        .row(vec!["Updated".to_string(), data.date_modified.to_string()]);
+    table.print()?;
+    if let Some(description) = &data.description {
+        writeln!(io::stdout().lock(), "\n{}", description)?;
+    }
+    RawKumaResult::Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rawkuma_scraper::{
+        enums::manga::{Genre, Order, Status},
+        types::ParseMode,
+    };
+
+    use super::{known, parse_range, Cli};
+
+    #[test]
+    fn chapter_ranges() {
+        assert_eq!(parse_range("5"), Ok(5.0..=5.0));
+        assert_eq!(parse_range("12.5"), Ok(12.5..=12.5));
+        assert_eq!(parse_range("1-10"), Ok(1.0..=10.0));
+        assert_eq!(parse_range(" 1 - 10 "), Ok(1.0..=10.0));
+        assert_eq!(parse_range("12-"), Ok(12.0..=f32::MAX));
+        assert_eq!(parse_range("-3"), Ok(0.0..=3.0));
+        assert_eq!(parse_range("-"), Ok(0.0..=f32::MAX));
+        assert_eq!(
+            parse_range("abc"),
+            Err("invalid chapter number 'abc'".to_string())
+        );
+        assert_eq!(
+            parse_range("1-x"),
+            Err("invalid chapter number 'x'".to_string())
+        );
+        assert_eq!(
+            parse_range(""),
+            Err("invalid chapter number ''".to_string())
+        );
+    }
+
+    #[test]
+    fn known_values() {
+        assert!(matches!(known::<Status>("ongoing"), Ok(Status::Ongoing)));
+        assert!(matches!(known::<Order>("update"), Ok(Order::Update)));
+        assert_eq!(
+            known::<Status>("finished").err(),
+            Some("unknown value 'finished'".to_string())
+        );
+        assert!(known::<Order>("Update").is_err());
+        assert!(matches!(known::<Genre>("action"), Ok(Genre::Action)));
+        for value in ["", " "] {
+            assert_eq!(
+                known::<Genre>(value).err(),
+                Some("the value is empty".to_string())
+            );
+        }
+        assert!(Cli::try_parse_from(["rawkuma", "list", "--genre", ""]).is_err());
+    }
+
+    #[test]
+    fn parse_modes() {
+        let cli = Cli::try_parse_from(["rawkuma", "home"]).unwrap();
+        assert_eq!(cli.get_parse_mode(), ParseMode::Lenient);
+        let cli = Cli::try_parse_from(["rawkuma", "home", "--strict"]).unwrap();
+        assert_eq!(cli.get_parse_mode(), ParseMode::Strict);
+        assert!(Cli::try_parse_from(["rawkuma", "home", "--lenient"]).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use std::io::{self, Write};
+
+use unicode_width::UnicodeWidthStr;
+
+/// Rows printed as left aligned columns under a header.
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+    pub fn row(&mut self, row: Vec<String>) -> &mut Self {
+        self.rows.push(row);
+        self
+    }
+    /// Print the table, returning the write error such as a closed pipe instead of panicking
+    pub fn print(&self) -> io::Result<()> {
+        self.write(&mut io::stdout().lock())
+    }
+    /// Write the table, the columns being as wide as their widest cell on a terminal
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.width()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+        write_row(out, &self.headers, &widths)?;
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        write_row(out, &rule, &widths)?;
+        for row in &self.rows {
+            write_row(out, row, &widths)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_row(out: &mut impl Write, cells: &[String], widths: &[usize]) -> io::Result<()> {
+    let line: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.width())))
+        .collect();
+    writeln!(out, "{}", line.join("  ").trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Table;
+
+    #[test]
+    fn wide_characters() {
+        let mut table = Table::new(&["Title", "Url"]);
+        table
+            .row(vec!["彼女、お借りします".to_string(), "a".to_string()])
+            .row(vec!["Kanojo".to_string(), "b".to_string()]);
+        let mut out = Vec::new();
+        table.write(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Title               Url\n\
+             ------------------  ---\n\
+             彼女、お借りします  a\n\
+             Kanojo              b\n"
+        );
+    }
+}
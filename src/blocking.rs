@@ -0,0 +1,78 @@
+use std::{future::Future, sync::Arc};
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::{
+    types::{
+        chapter::RawKumaChapterData,
+        error::Error,
+        home::RawKumaHomeData,
+        manga::{MangaListParameter, RawKumaMangaDetailData, RawKumaMangaList},
+        search::RawKumaSearch,
+        ChapterSlug, MangaSlug, RawKumaResult,
+    },
+    RawKumaClient,
+};
+
+/// Synchronous version of [`RawKumaClient`], for programs without an async runtime.
+///
+/// It drives the async client on its own single-threaded tokio runtime, so the
+/// transport, retries, rate limits and cache of the wrapped client all apply.
+/// Like `reqwest::blocking`, it must not be used from inside an async runtime.
+///
+/// ```no_run
+/// use rawkuma_scraper::blocking::RawKumaBlockingClient;
+///
+/// let client = RawKumaBlockingClient::new()?;
+/// let home = client.home()?;
+/// # Ok::<(), rawkuma_scraper::types::error::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct RawKumaBlockingClient {
+    client: RawKumaClient,
+    runtime: Arc<Runtime>,
+}
+
+impl RawKumaBlockingClient {
+    pub fn new() -> RawKumaResult<Self> {
+        Self::from_client(RawKumaClient::default())
+    }
+    /// Wrap an already configured client.
+    pub fn from_client(client: RawKumaClient) -> RawKumaResult<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        RawKumaResult::Ok(Self {
+            client,
+            runtime: Arc::new(runtime),
+        })
+    }
+    /// The wrapped async client
+    pub fn get_client(&self) -> &RawKumaClient {
+        &self.client
+    }
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+    pub fn home(&self) -> RawKumaResult<RawKumaHomeData> {
+        self.block_on(self.client.home())
+    }
+    pub fn manga_details<S>(&self, manga_slug: S) -> RawKumaResult<RawKumaMangaDetailData>
+    where
+        S: TryInto<MangaSlug>,
+        Error: From<S::Error>,
+    {
+        self.block_on(self.client.manga_details(manga_slug))
+    }
+    pub fn chapter<S>(&self, chapter_slug: S) -> RawKumaResult<RawKumaChapterData>
+    where
+        S: TryInto<ChapterSlug>,
+        Error: From<S::Error>,
+    {
+        self.block_on(self.client.chapter(chapter_slug))
+    }
+    pub fn search(&self, search_query: &dyn ToString, page: u32) -> RawKumaResult<RawKumaSearch> {
+        self.block_on(self.client.search(search_query, page))
+    }
+    pub fn manga_list(&self, params: &MangaListParameter) -> RawKumaResult<RawKumaMangaList> {
+        self.block_on(self.client.manga_list(params))
+    }
+}
@@ -0,0 +1,197 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    Url,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    transport::{RecordedResponse, TransportResponse},
+    types::{error::Error, RawKumaResult},
+};
+
+mod disk;
+mod memory;
+
+pub use disk::DiskCache;
+pub use memory::MemoryCache;
+
+/// Called with the errors of the cache, such as a page that could not be written
+pub type CacheErrorHandler = Arc<dyn Fn(&Error) + Send + Sync>;
+
+/// The kind of page a request is for, each kind having its own time to live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PageKind {
+    Home,
+    Search,
+    MangaList,
+    MangaDetails,
+    Chapter,
+}
+
+/// How long a cached page is served without asking the site.
+#[derive(Debug, Clone, Builder)]
+#[builder(build_fn(error = "crate::types::error::BuilderError"))]
+pub struct CacheTtl {
+    #[builder(default = "Duration::from_secs(60)")]
+    pub home: Duration,
+    #[builder(default = "Duration::from_secs(5 * 60)")]
+    pub search: Duration,
+    #[builder(default = "Duration::from_secs(5 * 60)")]
+    pub manga_list: Duration,
+    #[builder(default = "Duration::from_secs(60 * 60)")]
+    pub manga_details: Duration,
+    /// Chapters don't change once published
+    #[builder(default = "Duration::from_secs(7 * 24 * 60 * 60)")]
+    pub chapter: Duration,
+}
+
+impl Default for CacheTtl {
+    fn default() -> Self {
+        Self {
+            home: Duration::from_secs(60),
+            search: Duration::from_secs(5 * 60),
+            manga_list: Duration::from_secs(5 * 60),
+            manga_details: Duration::from_secs(60 * 60),
+            chapter: Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+impl CacheTtl {
+    pub fn get(&self, kind: PageKind) -> Duration {
+        match kind {
+            PageKind::Home => self.home,
+            PageKind::Search => self.search,
+            PageKind::MangaList => self.manga_list,
+            PageKind::MangaDetails => self.manga_details,
+            PageKind::Chapter => self.chapter,
+        }
+    }
+}
+
+/// A cached response and the time it was fetched or last revalidated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub response: RecordedResponse,
+    pub stored_at: DateTime<Utc>,
+}
+
+impl CacheEntry {
+    pub fn new(response: &TransportResponse) -> Self {
+        Self {
+            response: RecordedResponse::from(response),
+            stored_at: Utc::now(),
+        }
+    }
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        (Utc::now() - self.stored_at)
+            .to_std()
+            .map_or(true, |age| age < ttl)
+    }
+    /// `If-None-Match` and `If-Modified-Since` headers built from the cached validators
+    pub fn get_conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let validators = [(ETAG, IF_NONE_MATCH), (LAST_MODIFIED, IF_MODIFIED_SINCE)];
+        for (validator, header) in validators {
+            if let Some(value) = self
+                .response
+                .headers
+                .get(validator.as_str())
+                .and_then(|value| HeaderValue::from_str(value).ok())
+            {
+                headers.insert(header, value);
+            }
+        }
+        headers
+    }
+}
+
+/// Where the cached pages are kept.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    /// The entry of `url`, `None` when it is missing or unreadable
+    async fn get(&self, url: &Url) -> Option<CacheEntry>;
+    async fn put(&self, url: &Url, entry: CacheEntry) -> RawKumaResult<()>;
+    async fn remove(&self, url: &Url) -> RawKumaResult<()>;
+}
+
+/// Cache of the HTML pages fetched by a [`RawKumaClient`](crate::RawKumaClient).
+///
+/// A page younger than its [`CacheTtl`] is served without any request.
+/// An older one is revalidated with `If-None-Match` / `If-Modified-Since`
+/// when the site sent an `ETag` / `Last-Modified`, and served again on `304 Not Modified`.
+/// A page that cannot be stored is still returned, the error going to the error handler.
+///
+/// ```
+/// use rawkuma_scraper::{cache::ResponseCache, RawKumaClient};
+///
+/// let client = RawKumaClient::default().with_cache(ResponseCache::memory(256));
+/// ```
+#[derive(Clone)]
+pub struct ResponseCache {
+    backend: Arc<dyn CacheBackend>,
+    ttl: CacheTtl,
+    on_error: Option<CacheErrorHandler>,
+}
+
+impl ResponseCache {
+    pub fn new(backend: impl CacheBackend + 'static) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            ttl: CacheTtl::default(),
+            on_error: None,
+        }
+    }
+    /// In-memory cache keeping the `capacity` most recently used pages
+    pub fn memory(capacity: usize) -> Self {
+        Self::new(MemoryCache::new(capacity))
+    }
+    /// Cache kept as files in `directory`, shared between runs
+    pub fn disk(directory: impl Into<std::path::PathBuf>) -> Self {
+        Self::new(DiskCache::new(directory))
+    }
+    pub fn with_ttl(mut self, ttl: CacheTtl) -> Self {
+        self.ttl = ttl;
+        self
+    }
+    pub fn with_error_handler(mut self, handler: CacheErrorHandler) -> Self {
+        self.on_error = Some(handler);
+        self
+    }
+    pub fn get_ttl(&self) -> &CacheTtl {
+        &self.ttl
+    }
+    fn report<T>(&self, result: RawKumaResult<T>) -> RawKumaResult<T> {
+        if let (Err(error), Some(handler)) = (&result, &self.on_error) {
+            handler(error);
+        }
+        result
+    }
+    pub async fn get(&self, url: &Url) -> Option<CacheEntry> {
+        self.backend.get(url).await
+    }
+    /// Store `response`, the error also going to the error handler
+    pub async fn put(&self, url: &Url, response: &TransportResponse) -> RawKumaResult<()> {
+        self.report(self.backend.put(url, CacheEntry::new(response)).await)
+    }
+    /// Mark a stale entry as valid again after a `304 Not Modified`
+    pub async fn refresh(
+        &self,
+        url: &Url,
+        mut entry: CacheEntry,
+    ) -> RawKumaResult<TransportResponse> {
+        entry.stored_at = Utc::now();
+        let response = TransportResponse::try_from(&entry.response)?;
+        let _ = self.report(self.backend.put(url, entry).await);
+        RawKumaResult::Ok(response)
+    }
+    /// Remove the entry of `url`, the error also going to the error handler
+    pub async fn remove(&self, url: &Url) -> RawKumaResult<()> {
+        self.report(self.backend.remove(url).await)
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::hash_map::RandomState,
+    fs,
+    hash::{BuildHasher, Hasher},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::types::RawKumaResult;
+
+use super::{CacheBackend, CacheEntry};
+
+/// Cache keeping each page as a JSON file in a directory.
+///
+/// The files are named after a hash of the full url, in a directory per host,
+/// `https://rawkuma.com/` is kept in `rawkuma.com/<hash>.json`. Each file also
+/// stores the url it was requested for, so a file holding another url is a miss.
+/// Unreadable files are treated as missing. The files are small, so they are
+/// read and written with blocking calls, which work on any runtime.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    directory: PathBuf,
+}
+
+/// Content of a cache file
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    /// Requested url, the response may come from another one after a redirection
+    url: Url,
+    entry: CacheEntry,
+}
+
+/// 64-bit FNV-1a hash of `data`, stable across builds and platforms
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Number of the files written by this process, to name their partial files
+static PARTIAL_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// A temporary file next to `path` that no other writer uses, made of the
+/// process id, a counter and a random suffix
+fn get_partial_path(path: &Path) -> PathBuf {
+    let suffix = RandomState::new().build_hasher().finish();
+    path.with_extension(format!(
+        "json.{}-{}-{:016x}.partial",
+        process::id(),
+        PARTIAL_COUNT.fetch_add(1, Ordering::Relaxed),
+        suffix
+    ))
+}
+
+impl DiskCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+    pub fn get_entry_path(&self, url: &Url) -> PathBuf {
+        self.directory
+            .join(url.host_str().unwrap_or_default())
+            .join(format!("{:016x}.json", fnv1a(url.as_str().as_bytes())))
+    }
+}
+
+#[async_trait]
+impl CacheBackend for DiskCache {
+    async fn get(&self, url: &Url) -> Option<CacheEntry> {
+        let content = fs::read(self.get_entry_path(url)).ok()?;
+        let file: DiskEntry = serde_json::from_slice(&content).ok()?;
+        (&file.url == url).then_some(file.entry)
+    }
+    async fn put(&self, url: &Url, entry: CacheEntry) -> RawKumaResult<()> {
+        let path = self.get_entry_path(url);
+        let content = serde_json::to_vec(&DiskEntry {
+            url: url.clone(),
+            entry,
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename so a concurrent reader never sees a partial file
+        let partial = get_partial_path(&path);
+        let result = fs::write(&partial, content).and_then(|_| fs::rename(&partial, path));
+        if result.is_err() {
+            let _ = fs::remove_file(partial);
+        }
+        RawKumaResult::Ok(result?)
+    }
+    async fn remove(&self, url: &Url) -> RawKumaResult<()> {
+        match fs::remove_file(self.get_entry_path(url)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => RawKumaResult::Err(e.into()),
+            _ => RawKumaResult::Ok(()),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use reqwest::Url;
+
+use crate::types::RawKumaResult;
+
+use super::{CacheBackend, CacheEntry};
+
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<Url, (CacheEntry, u64)>,
+    /// Urls ordered by last use
+    recency: BTreeMap<u64, Url>,
+    tick: u64,
+}
+
+impl LruState {
+    fn touch(&mut self, url: &Url) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some((_, last_used)) = self.entries.get_mut(url) {
+            self.recency.remove(last_used);
+            *last_used = tick;
+            self.recency.insert(tick, url.clone());
+        }
+    }
+}
+
+/// In-memory cache dropping the least recently used page when full.
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Default::default(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.state.lock().map_or(0, |state| state.entries.len())
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    async fn get(&self, url: &Url) -> Option<CacheEntry> {
+        let mut state = self.state.lock().ok()?;
+        state.touch(url);
+        state.entries.get(url).map(|(entry, _)| entry.clone())
+    }
+    async fn put(&self, url: &Url, entry: CacheEntry) -> RawKumaResult<()> {
+        let Ok(mut state) = self.state.lock() else {
+            return RawKumaResult::Ok(());
+        };
+        if let Some((_, last_used)) = state.entries.remove(url) {
+            state.recency.remove(&last_used);
+        }
+        while state.entries.len() >= self.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        state.entries.insert(url.clone(), (entry, tick));
+        state.recency.insert(tick, url.clone());
+        RawKumaResult::Ok(())
+    }
+    async fn remove(&self, url: &Url) -> RawKumaResult<()> {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some((_, last_used)) = state.entries.remove(url) {
+                state.recency.remove(&last_used);
+            }
+        }
+        RawKumaResult::Ok(())
+    }
+}
@@ -1,114 +1,389 @@
+use std::{collections::HashSet, sync::Arc};
+
 use async_trait::async_trait;
 use derive_builder::Builder;
-use reqwest::{Client, Response, Url};
-use scraper::Html;
+use futures::{stream, Stream};
+use reqwest::{header::HeaderMap, Client, StatusCode, Url};
 
 use crate::{
-    constant::BASE_URL,
+    cache::{PageKind, ResponseCache},
     parser::{
-        chapter::RawKumaChapterParser, home::RawKumaHomeParser,
-        manga_details::RawKumaMangaDetailParser, search::RawKumaSearchParser, HtmlParser,
+        parse_chapter_with_mode, parse_home_with_mode, parse_manga_details_with_mode,
+        parse_manga_list_with_mode, parse_search_with_mode, SelectorProfile,
     },
+    source::{Source, SourceConfig},
+    transport::{ReqwestTransport, Transport, TransportResponse},
     types::{
-        chapter::RawKumaChapterData, home::RawKumaHomeData, manga::RawKumaMangaDetailData,
-        search::RawKumaSearch, FromHtmlParser, RawKumaResult,
+        chapter::RawKumaChapterData,
+        error::Error,
+        home::RawKumaHomeData,
+        manga::{MangaListParameter, RawKumaMangaDetailData, RawKumaMangaList},
+        search::RawKumaSearch,
+        ChapterSlug, MangaSlug, ParseMode, ParseWarningHandler, Parsed, RawKumaResult, ToUrlParam,
     },
 };
 
+mod rate_limit;
+mod retry;
+pub(crate) mod timer;
+
+pub use rate_limit::{RateLimit, RateLimiter};
+pub use retry::{RetryPolicy, RetryPolicyBuilder};
+
+/// Client of the rawkuma website.
+///
+/// Every method takes `&self`, so a single client, or an `Arc<RawKumaClient>`,
+/// can serve many concurrent requests. Clones share the transport, the rate
+/// limits and the cache.
 #[derive(Clone, Builder)]
+#[builder(build_fn(error = "crate::types::error::BuilderError"))]
 pub struct RawKumaClient {
-    http_client: Client,
-    api_url: Url,
+    /// Sends the requests, over the network with reqwest by default
+    #[builder(default = "Arc::new(ReqwestTransport::default())")]
+    transport: Arc<dyn Transport>,
+    /// The site, rawkuma by default
+    #[builder(default)]
+    source: SourceConfig,
+    /// Retries of the failed requests, see [`RetryPolicy`]
+    #[builder(default)]
+    retry_policy: RetryPolicy,
+    /// Pacing of the requests, see [`RateLimiter`]
+    #[builder(default)]
+    rate_limiter: RateLimiter,
+    /// Cache of the HTML pages, disabled by default
+    #[builder(default, setter(strip_option))]
+    cache: Option<ResponseCache>,
+    /// Handling of the list items that fail to parse, lenient by default
+    #[builder(default)]
+    parse_mode: ParseMode,
+    /// Receives the warnings of the lenient parses
+    #[builder(default, setter(strip_option))]
+    on_parse_warning: Option<ParseWarningHandler>,
+}
+
+impl RawKumaClientBuilder {
+    /// Root url of the site, keeping the other settings of the source.
+    pub fn api_url(&mut self, api_url: Url) -> &mut Self {
+        self.source
+            .get_or_insert_with(SourceConfig::rawkuma)
+            .base_url = api_url;
+        self
+    }
+    /// Selectors of the site, keeping the other settings of the source.
+    pub fn selector_profile(&mut self, profile: SelectorProfile) -> &mut Self {
+        self.source
+            .get_or_insert_with(SourceConfig::rawkuma)
+            .selector_profile = Arc::new(profile);
+        self
+    }
+    /// Limit the requests to the site's pages, keeping the current image limit.
+    pub fn page_rate_limit(&mut self, limit: RateLimit) -> &mut Self {
+        let images = self
+            .rate_limiter
+            .as_ref()
+            .and_then(|rate_limiter| rate_limiter.get_image_limit());
+        self.rate_limiter = Some(RateLimiter::new(Some(limit), images));
+        self
+    }
+    /// Limit the requests to the other hosts, such as the image CDN, keeping the current page limit.
+    pub fn image_rate_limit(&mut self, limit: RateLimit) -> &mut Self {
+        let pages = self
+            .rate_limiter
+            .as_ref()
+            .and_then(|rate_limiter| rate_limiter.get_page_limit());
+        self.rate_limiter = Some(RateLimiter::new(pages, Some(limit)));
+        self
+    }
 }
 
+/// Fetch and parse the pages at custom urls, such as another mirror of the site.
 #[async_trait]
 pub trait RawKumaClientFromUrl {
-    async fn manga_details(&mut self, url: Url) -> RawKumaResult<RawKumaMangaDetailData>;
-    async fn chapter(&mut self, url: Url) -> RawKumaResult<RawKumaChapterData>;
-    async fn home(&mut self, url: Url) -> RawKumaResult<RawKumaHomeData>;
-    async fn search(&mut self, url: Url) -> RawKumaResult<RawKumaSearch>;
+    async fn manga_details(&self, url: Url) -> RawKumaResult<RawKumaMangaDetailData>;
+    async fn chapter(&self, url: Url) -> RawKumaResult<RawKumaChapterData>;
+    async fn home(&self, url: Url) -> RawKumaResult<RawKumaHomeData>;
+    async fn search(&self, url: Url) -> RawKumaResult<RawKumaSearch>;
+    async fn manga_list(&self, url: Url) -> RawKumaResult<RawKumaMangaList>;
 }
 
 impl Default for RawKumaClient {
     fn default() -> Self {
-        Self {
-            http_client: Client::new(),
-            api_url: Url::parse(BASE_URL).expect("Error on parsing the BASE_URL"),
-        }
+        Self::new(Client::new())
     }
 }
 
 #[async_trait]
 impl RawKumaClientFromUrl for RawKumaClient {
-    async fn manga_details(&mut self, url: Url) -> RawKumaResult<RawKumaMangaDetailData> {
-        type Output = RawKumaMangaDetailData;
-        let res = self.send_get(url).await?;
-        let html = Html::parse_document(res.text().await?.as_str());
-        let parser = RawKumaMangaDetailParser::init(&html)?;
-        RawKumaResult::Ok(<Output as FromHtmlParser<RawKumaMangaDetailParser>>::from(
-            parser,
+    async fn manga_details(&self, url: Url) -> RawKumaResult<RawKumaMangaDetailData> {
+        let res = self.send_get(url.clone(), PageKind::MangaDetails).await?;
+        self.report_warnings(parse_manga_details_with_mode(
+            res.text().as_str(),
+            &url,
+            self.source.parse_context(self.parse_mode),
+        )?)
+    }
+    async fn chapter(&self, url: Url) -> RawKumaResult<RawKumaChapterData> {
+        let res = self.send_get(url.clone(), PageKind::Chapter).await?;
+        self.report_warnings(parse_chapter_with_mode(
+            res.text().as_str(),
+            &url,
+            self.source.parse_context(self.parse_mode),
         )?)
     }
-    async fn chapter(&mut self, url: Url) -> RawKumaResult<RawKumaChapterData> {
-        type Output = RawKumaChapterData;
-        let res = self.send_get(url).await?;
-        let html = Html::parse_document(res.text().await?.as_str());
-        let parser = RawKumaChapterParser::init(&html)?;
-        RawKumaResult::Ok(<Output as FromHtmlParser<RawKumaChapterParser>>::from(
-            parser,
+    async fn home(&self, url: Url) -> RawKumaResult<RawKumaHomeData> {
+        let res = self.send_get(url.clone(), PageKind::Home).await?;
+        self.report_warnings(parse_home_with_mode(
+            res.text().as_str(),
+            &url,
+            self.source.parse_context(self.parse_mode),
         )?)
     }
-    async fn home(&mut self, url: Url) -> RawKumaResult<RawKumaHomeData> {
-        type Output = RawKumaHomeData;
-        let res = self.send_get(url).await?;
-        let html = Html::parse_document(res.text().await?.as_str());
-        let home = RawKumaHomeParser::init(&html)?;
-        RawKumaResult::Ok(<Output as FromHtmlParser<RawKumaHomeParser>>::from(home)?)
+    async fn search(&self, url: Url) -> RawKumaResult<RawKumaSearch> {
+        let res = self.send_get(url.clone(), PageKind::Search).await?;
+        self.report_warnings(parse_search_with_mode(
+            res.text().as_str(),
+            &url,
+            self.source.parse_context(self.parse_mode),
+        )?)
     }
-    async fn search(&mut self, url: Url) -> RawKumaResult<RawKumaSearch> {
-        type Output = RawKumaSearch;
-        let res = self.send_get(url).await?;
-        let html = Html::parse_document(res.text().await?.as_str());
-        let home = RawKumaSearchParser::init(&html)?;
-        RawKumaResult::Ok(<Output as FromHtmlParser<RawKumaSearchParser>>::from(home)?)
+    async fn manga_list(&self, url: Url) -> RawKumaResult<RawKumaMangaList> {
+        let res = self.send_get(url.clone(), PageKind::MangaList).await?;
+        self.report_warnings(parse_manga_list_with_mode(
+            res.text().as_str(),
+            &url,
+            self.source.parse_context(self.parse_mode),
+        )?)
     }
 }
 
 impl RawKumaClient {
     pub fn new(client: Client) -> Self {
+        Self::with_transport(ReqwestTransport::new(client))
+    }
+    /// Send the requests with `transport` instead of reqwest.
+    pub fn with_transport(transport: impl Transport + 'static) -> Self {
         Self {
-            http_client: client,
-            api_url: Url::parse(BASE_URL).expect("Error on parsing the BASE_URL"),
+            transport: Arc::new(transport),
+            source: SourceConfig::rawkuma(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+            cache: None,
+            parse_mode: ParseMode::default(),
+            on_parse_warning: None,
+        }
+    }
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    pub fn with_parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+    /// Call `handler` with every warning of the lenient parses, to notice a layout change of the site.
+    pub fn with_parse_warning_handler(mut self, handler: ParseWarningHandler) -> Self {
+        self.on_parse_warning = Some(handler);
+        self
+    }
+    /// Browse a sibling site of rawkuma, built on the same theme.
+    pub fn with_source(mut self, source: SourceConfig) -> Self {
+        self.source = source;
+        self
+    }
+    /// Parse the pages with `profile` instead of the rawkuma selectors.
+    pub fn with_selector_profile(mut self, profile: SelectorProfile) -> Self {
+        self.source.selector_profile = Arc::new(profile);
+        self
+    }
+    fn report_warnings<T>(&self, parsed: Parsed<T>) -> RawKumaResult<T> {
+        if let Some(handler) = &self.on_parse_warning {
+            parsed.warnings.iter().for_each(|warning| handler(warning));
         }
+        RawKumaResult::Ok(parsed.data)
     }
-    async fn send_get(&mut self, url: Url) -> RawKumaResult<Response> {
-        let req = self.http_client.get(url).build()?;
-        let res = self.http_client.execute(req).await?;
+    /// Fetch a page, failing on non-2xx statuses and challenge pages before any parsing.
+    ///
+    /// When a cache is set, fresh pages are served from it and stale ones are revalidated.
+    async fn send_get(&self, url: Url, kind: PageKind) -> RawKumaResult<TransportResponse> {
+        let cache = match &self.cache {
+            None => {
+                return self
+                    .send_get_with_headers(url, HeaderMap::new())
+                    .await?
+                    .error_for_status()
+            }
+            Some(cache) => cache,
+        };
+        let entry = cache.get(&url).await;
+        let headers = match &entry {
+            Some(entry) if entry.is_fresh(cache.get_ttl().get(kind)) => {
+                return TransportResponse::try_from(&entry.response)
+            }
+            Some(entry) => entry.get_conditional_headers(),
+            None => HeaderMap::new(),
+        };
+        let res = self.send_get_with_headers(url.clone(), headers).await?;
+        if let (StatusCode::NOT_MODIFIED, Some(entry)) = (res.status, entry) {
+            return cache.refresh(&url, entry).await;
+        }
+        let res = res.error_for_status()?;
+        // A page that cannot be stored is still returned, the cache reports the error
+        let _ = cache.put(&url, &res).await;
         RawKumaResult::Ok(res)
     }
-    pub async fn home(&mut self) -> RawKumaResult<RawKumaHomeData> {
-        let url = self.api_url.clone();
+    pub(crate) async fn send_get_with_headers(
+        &self,
+        url: Url,
+        headers: HeaderMap,
+    ) -> RawKumaResult<TransportResponse> {
+        let mut attempt: u32 = 1;
+        loop {
+            self.rate_limiter.acquire(&url, &self.source.base_url).await;
+            let can_retry = attempt < self.retry_policy.max_attempts;
+            let delay = match self.transport.get(url.clone(), headers.clone()).await {
+                Ok(res)
+                    if can_retry
+                        && RetryPolicy::is_retryable_status(res.status)
+                        && !res.is_challenge_page() =>
+                {
+                    self.retry_policy.get_delay(attempt, Some(&res.headers))
+                }
+                Err(e) if can_retry && e.is_retryable() => {
+                    self.retry_policy.get_delay(attempt, None)
+                }
+                result => return result,
+            };
+            timer::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+    /// Url of a reader page on the site
+    pub(crate) fn chapter_url(&self, chapter_slug: &ChapterSlug) -> RawKumaResult<Url> {
+        chapter_slug.to_url(&self.source.base_url)
+    }
+    /// Url of a series page on the site
+    fn manga_url(&self, manga_slug: &MangaSlug) -> RawKumaResult<Url> {
+        manga_slug.to_url(&self.source.base_url)
+    }
+    pub async fn home(&self) -> RawKumaResult<RawKumaHomeData> {
+        let url = self.source.base_url.clone();
         RawKumaClientFromUrl::home(self, url).await
     }
-    pub async fn manga_details(
-        &mut self,
-        manga_slug: &dyn ToString,
-    ) -> RawKumaResult<RawKumaMangaDetailData> {
-        let url = Url::parse(format!("{}{}", self.api_url, manga_slug.to_string()).as_str())?;
+    /// Fetch a series page.
+    ///
+    /// `manga_slug` is a [`MangaSlug`] or anything that converts into one: the slug
+    /// itself, `manga/<slug>/` or the full url of the page, as a `&str`, `String` or `&Url`.
+    /// The host of an url is not checked, [`Source::manga_details`] rejects the urls of other sites.
+    pub async fn manga_details<S>(&self, manga_slug: S) -> RawKumaResult<RawKumaMangaDetailData>
+    where
+        S: TryInto<MangaSlug>,
+        Error: From<S::Error>,
+    {
+        let url = self.manga_url(&manga_slug.try_into()?)?;
         RawKumaClientFromUrl::manga_details(self, url).await
     }
-    pub async fn chapter(
-        &mut self,
-        chapter_slug: &dyn ToString,
-    ) -> RawKumaResult<RawKumaChapterData> {
-        let url = Url::parse(format!("{}{}", self.api_url, chapter_slug.to_string()).as_str())?;
+    /// Fetch a reader page.
+    ///
+    /// `chapter_slug` is a [`ChapterSlug`] or anything that converts into one: the slug
+    /// itself or the full url of the page, as a `&str`, `String` or `&Url`.
+    /// The host of an url is not checked, [`Source::chapter`] rejects the urls of other sites.
+    pub async fn chapter<S>(&self, chapter_slug: S) -> RawKumaResult<RawKumaChapterData>
+    where
+        S: TryInto<ChapterSlug>,
+        Error: From<S::Error>,
+    {
+        let url = self.chapter_url(&chapter_slug.try_into()?)?;
         RawKumaClientFromUrl::chapter(self, url).await
     }
-    pub async fn search(&mut self, search_query: &dyn ToString) -> RawKumaResult<RawKumaSearch> {
-        let url = Url::parse_with_params(
-            self.api_url.to_string().as_str(),
+    fn search_url(&self, search_query: &dyn ToString, page: u32) -> RawKumaResult<Url> {
+        let base = if page > 1 {
+            self.source
+                .base_url
+                .join(format!("page/{}/", page).as_str())?
+        } else {
+            self.source.base_url.clone()
+        };
+        RawKumaResult::Ok(Url::parse_with_params(
+            base.as_str(),
             [("s", search_query.to_string().as_str())],
+        )?)
+    }
+    pub async fn search(
+        &self,
+        search_query: &dyn ToString,
+        page: u32,
+    ) -> RawKumaResult<RawKumaSearch> {
+        let url = self.search_url(search_query, page)?;
+        RawKumaClientFromUrl::search(self, url).await
+    }
+    /// Walk every result page of a search, starting from the first one.
+    ///
+    /// Pages are fetched lazily by following the `Next` link of the previous page.
+    /// The stream ends after the last page, after the first error, or when a
+    /// `Next` link points back to a page already fetched.
+    pub fn search_stream(
+        &self,
+        search_query: &dyn ToString,
+    ) -> RawKumaResult<impl Stream<Item = RawKumaResult<RawKumaSearch>>> {
+        let url = self.search_url(search_query, 1)?;
+        RawKumaResult::Ok(stream::unfold(
+            Some((self.clone(), url, HashSet::new())),
+            |state| async move {
+                let (client, url, mut visited) = state?;
+                visited.insert(url.clone());
+                match RawKumaClientFromUrl::search(&client, url).await {
+                    Ok(page) => {
+                        let next = page
+                            .pagination
+                            .next_url
+                            .clone()
+                            .filter(|next| !visited.contains(next))
+                            .map(|next| (client, next, visited));
+                        Some((Ok(page), next))
+                    }
+                    Err(e) => Some((Err(e), None)),
+                }
+            },
+        ))
+    }
+    pub async fn manga_list(&self, params: &MangaListParameter) -> RawKumaResult<RawKumaMangaList> {
+        let url = Url::parse_with_params(
+            self.source.base_url.join("manga/")?.as_str(),
+            params.to_url_param(),
         )?;
+        RawKumaClientFromUrl::manga_list(self, url).await
+    }
+}
+
+#[async_trait]
+impl Source for RawKumaClient {
+    fn config(&self) -> &SourceConfig {
+        &self.source
+    }
+    async fn home(&self) -> RawKumaResult<RawKumaHomeData> {
+        RawKumaClient::home(self).await
+    }
+    async fn search(&self, query: &str, page: u32) -> RawKumaResult<RawKumaSearch> {
+        let url = self.search_url(&query, page)?;
         RawKumaClientFromUrl::search(self, url).await
     }
+    async fn manga_list(&self, params: &MangaListParameter) -> RawKumaResult<RawKumaMangaList> {
+        RawKumaClient::manga_list(self, params).await
+    }
+    async fn manga_details(&self, manga: &str) -> RawKumaResult<RawKumaMangaDetailData> {
+        let url = self.manga_url(&MangaSlug::parse_on(manga, &self.source.base_url)?)?;
+        RawKumaClientFromUrl::manga_details(self, url).await
+    }
+    async fn chapter(&self, chapter: &str) -> RawKumaResult<RawKumaChapterData> {
+        let url = self.chapter_url(&ChapterSlug::parse_on(chapter, &self.source.base_url)?)?;
+        RawKumaClientFromUrl::chapter(self, url).await
+    }
 }
@@ -0,0 +1,117 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use reqwest::Url;
+
+use super::timer::{self, Instant};
+
+/// A number of requests per second, with bursts of up to `burst` requests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+        }
+    }
+    /// `requests_per_second` without bursts
+    pub fn per_second(requests_per_second: f64) -> Self {
+        Self::new(requests_per_second, 1)
+    }
+}
+
+struct BucketState {
+    /// Negative when requests are already waiting for a token
+    tokens: f64,
+    updated_at: Instant,
+}
+
+struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: limit.burst.max(1) as f64,
+                updated_at: Instant::now(),
+            }),
+        }
+    }
+    /// Take a token and return how long to wait before it is available.
+    ///
+    /// Tokens are reserved in call order, so the waiting requests are served first come, first served.
+    fn reserve(&self) -> Duration {
+        let rate = self.limit.requests_per_second;
+        if rate <= 0.0 || !rate.is_finite() {
+            return Duration::ZERO;
+        }
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.updated_at).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rate).min(self.limit.burst.max(1) as f64);
+        state.updated_at = now;
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+    async fn acquire(&self) {
+        let delay = self.reserve();
+        if !delay.is_zero() {
+            timer::sleep(delay).await;
+        }
+    }
+}
+
+/// Paces the requests of a [`RawKumaClient`](crate::RawKumaClient).
+///
+/// Requests to the site's own host use the page budget, requests to any
+/// other host, such as the image CDN, use the image budget.
+/// Both are unlimited by default. Clones share the same budgets.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    pages: Option<Arc<TokenBucket>>,
+    images: Option<Arc<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(pages: Option<RateLimit>, images: Option<RateLimit>) -> Self {
+        Self {
+            pages: pages.map(|limit| Arc::new(TokenBucket::new(limit))),
+            images: images.map(|limit| Arc::new(TokenBucket::new(limit))),
+        }
+    }
+    pub fn get_page_limit(&self) -> Option<RateLimit> {
+        self.pages.as_ref().map(|bucket| bucket.limit)
+    }
+    pub fn get_image_limit(&self) -> Option<RateLimit> {
+        self.images.as_ref().map(|bucket| bucket.limit)
+    }
+    /// Wait until a request to `url` is allowed, `api_url` being the site's root.
+    pub async fn acquire(&self, url: &Url, api_url: &Url) {
+        let bucket = if url.host() == api_url.host() {
+            &self.pages
+        } else {
+            &self.images
+        };
+        if let Some(bucket) = bucket {
+            bucket.acquire().await;
+        }
+    }
+}
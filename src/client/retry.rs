@@ -0,0 +1,114 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
+
+/// How [`RawKumaClient`](crate::RawKumaClient) retries the requests that failed
+/// because of a connection error, a `429 Too Many Requests` or a `5xx` status.
+///
+/// ```
+/// use std::time::Duration;
+/// use rawkuma_scraper::{RawKumaClientBuilder, RetryPolicyBuilder};
+///
+/// let client = RawKumaClientBuilder::default()
+///     .retry_policy(
+///         RetryPolicyBuilder::default()
+///             .max_attempts(5_u32)
+///             .initial_backoff(Duration::from_secs(1))
+///             .build()?,
+///     )
+///     .build()?;
+/// # Ok::<(), rawkuma_scraper::types::error::BuilderError>(())
+/// ```
+#[derive(Debug, Clone, Builder)]
+#[builder(build_fn(error = "crate::types::error::BuilderError"))]
+pub struct RetryPolicy {
+    /// Total number of attempts, the first one included
+    #[builder(default = "3")]
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    #[builder(default = "Duration::from_millis(500)")]
+    pub initial_backoff: Duration,
+    /// Upper bound of every delay, `Retry-After` included
+    #[builder(default = "Duration::from_secs(30)")]
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after each retry
+    #[builder(default = "2.0")]
+    pub multiplier: f64,
+    /// Pick each delay at random between half and all of the backoff
+    #[builder(default = "true")]
+    pub jitter: bool,
+    /// Wait for the delay given by the `Retry-After` header when there is one
+    #[builder(default = "true")]
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Send every request only once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+    /// `408 Request Timeout`, `429 Too Many Requests` and the `5xx` statuses are worth a retry
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status.is_server_error()
+    }
+    /// Read the `Retry-After` header, given either in seconds or as an HTTP date.
+    pub fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+        let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let date = DateTime::parse_from_rfc2822(value).ok()?;
+        (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+    }
+    /// Exponential backoff before the retry number `retry`, starting from 1
+    pub fn get_backoff(&self, retry: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(retry.saturating_sub(1) as i32);
+        let backoff = self
+            .initial_backoff
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_backoff);
+        if self.jitter {
+            let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+            backoff.mul_f64(0.5 + random / 2.0)
+        } else {
+            backoff
+        }
+    }
+    /// Delay before the retry number `retry`, `headers` being those of the failed response if any
+    pub fn get_delay(&self, retry: u32, headers: Option<&HeaderMap>) -> Duration {
+        match headers.and_then(Self::get_retry_after) {
+            Some(retry_after) if self.respect_retry_after => retry_after.min(self.max_backoff),
+            _ => self.get_backoff(retry),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Timers of the client, on `futures_timer` so they work on any runtime, or none.
+
+use std::{future::Future, time::Duration};
+
+pub(crate) use std::time::Instant;
+
+pub(crate) async fn sleep(duration: Duration) {
+    futures_timer::Delay::new(duration).await
+}
+
+/// Output of `future`, or `None` when it takes longer than `duration`
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    use futures::future::{select, Either};
+
+    let future = std::pin::pin!(future);
+    match select(future, futures_timer::Delay::new(duration)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
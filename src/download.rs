@@ -0,0 +1,247 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use derive_builder::Builder;
+use futures::{stream, Stream, StreamExt};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE, REFERER},
+    Url,
+};
+
+use crate::{
+    client::RawKumaClientFromUrl,
+    types::{chapter::RawKumaChapterData, error::Error, ChapterSlug, RawKumaResult},
+    RawKumaClient,
+};
+
+mod format;
+
+pub use format::ImageFormat;
+
+/// Called each time a page of the chapter has been downloaded.
+pub type ProgressCallback = Arc<dyn Fn(DownloadProgress) + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct DownloadProgress {
+    /// Number of pages downloaded so far
+    pub downloaded: usize,
+    pub total: usize,
+    /// Zero based index of the page that has just been downloaded
+    pub index: usize,
+    pub url: Url,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChapterPage {
+    /// Zero based index of the page in the chapter
+    pub index: usize,
+    pub url: Url,
+    pub format: ImageFormat,
+    pub data: Vec<u8>,
+}
+
+impl ChapterPage {
+    /// File name of the page, numbered from 1 and zero-padded to `width` digits
+    pub fn file_name(&self, width: usize) -> String {
+        format!(
+            "{:0width$}.{}",
+            self.index + 1,
+            self.format.extension(),
+            width = width
+        )
+    }
+    /// Width and height of the image in pixels, read from its header
+    pub fn get_dimensions(&self) -> Option<(usize, usize)> {
+        imagesize::blob_size(&self.data)
+            .ok()
+            .map(|size| (size.width, size.height))
+    }
+}
+
+/// Download the images of a chapter.
+///
+/// ```no_run
+/// # async fn run() -> rawkuma_scraper::types::RawKumaResult<()> {
+/// use rawkuma_scraper::{download::ChapterDownloaderBuilder, RawKumaClient};
+///
+/// let downloader = ChapterDownloaderBuilder::default()
+///     .client(RawKumaClient::default())
+///     .concurrency(8_usize)
+///     .build()?;
+/// downloader.download_slug("some-chapter-slug", "./chapter").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Builder)]
+#[builder(build_fn(error = "crate::types::error::BuilderError"))]
+pub struct ChapterDownloader {
+    client: RawKumaClient,
+    /// Maximum number of pages downloaded at the same time
+    #[builder(default = "4")]
+    concurrency: usize,
+    #[builder(default, setter(strip_option))]
+    on_progress: Option<ProgressCallback>,
+}
+
+impl ChapterDownloader {
+    pub fn new(client: RawKumaClient) -> Self {
+        Self {
+            client,
+            concurrency: 4,
+            on_progress: None,
+        }
+    }
+    pub fn get_client(&self) -> &RawKumaClient {
+        &self.client
+    }
+    /// The image hosts expect the reader page's origin as `Referer`
+    pub fn get_referer(chapter_url: &Url) -> Option<HeaderValue> {
+        HeaderValue::from_str(format!("{}/", chapter_url.origin().ascii_serialization()).as_str())
+            .ok()
+    }
+    /// Width used to zero-pad the page file names
+    pub fn get_file_name_width(total: usize) -> usize {
+        total.to_string().len().max(3)
+    }
+    pub fn get_images(chapter: &RawKumaChapterData) -> RawKumaResult<&Vec<Url>> {
+        chapter
+            .sources
+            .sources
+            .iter()
+            .find(|source| !source.images.is_empty())
+            .map(|source| &source.images)
+            .ok_or(Error::NoImageSource)
+    }
+    /// Download a page, retried by the client's [`RetryPolicy`](crate::RetryPolicy)
+    async fn fetch_page(
+        &self,
+        index: usize,
+        url: &Url,
+        headers: &HeaderMap,
+    ) -> RawKumaResult<ChapterPage> {
+        let res = self
+            .client
+            .send_get_with_headers(url.clone(), headers.clone())
+            .await?
+            .error_for_status()?;
+        let content_type = res
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        let data = res.body;
+        let format = ImageFormat::detect(&data, content_type.as_deref(), url)
+            .ok_or(Error::UnknownImageFormat(url.clone()))?;
+        RawKumaResult::Ok(ChapterPage {
+            index,
+            url: url.clone(),
+            format,
+            data,
+        })
+    }
+    /// Download a single image, such as a series cover.
+    ///
+    /// `page_url` is the page the image is shown on, used to build the `Referer` header.
+    pub async fn fetch_image(&self, url: &Url, page_url: &Url) -> RawKumaResult<ChapterPage> {
+        let mut headers = HeaderMap::new();
+        if let Some(referer) = Self::get_referer(page_url) {
+            headers.insert(REFERER, referer);
+        }
+        self.fetch_page(0, url, &headers).await
+    }
+    /// Stream of the chapter pages in completion order.
+    ///
+    /// `chapter_url` is the url of the reader page, used to build the `Referer` header.
+    pub fn pages_stream<'a>(
+        &'a self,
+        chapter: &RawKumaChapterData,
+        chapter_url: &Url,
+    ) -> RawKumaResult<impl Stream<Item = RawKumaResult<ChapterPage>> + 'a> {
+        let images = Self::get_images(chapter)?.clone();
+        let total = images.len();
+        let mut headers = HeaderMap::new();
+        if let Some(referer) = Self::get_referer(chapter_url) {
+            headers.insert(REFERER, referer);
+        }
+        let mut downloaded: usize = 0;
+        RawKumaResult::Ok(
+            stream::iter(images.into_iter().enumerate())
+                .map(move |(index, url)| {
+                    let headers = headers.clone();
+                    async move { self.fetch_page(index, &url, &headers).await }
+                })
+                .buffer_unordered(self.concurrency.max(1))
+                .map(move |page| {
+                    if let (Ok(page), Some(on_progress)) = (&page, &self.on_progress) {
+                        downloaded += 1;
+                        on_progress(DownloadProgress {
+                            downloaded,
+                            total,
+                            index: page.index,
+                            url: page.url.clone(),
+                        });
+                    }
+                    page
+                }),
+        )
+    }
+    /// Download every page of the chapter in memory, in reading order.
+    pub async fn fetch_pages(
+        &self,
+        chapter: &RawKumaChapterData,
+        chapter_url: &Url,
+    ) -> RawKumaResult<Vec<ChapterPage>> {
+        let mut pages: Vec<ChapterPage> = Vec::new();
+        let mut stream = Box::pin(self.pages_stream(chapter, chapter_url)?);
+        while let Some(page) = stream.next().await {
+            pages.push(page?);
+        }
+        pages.sort_by_key(|page| page.index);
+        RawKumaResult::Ok(pages)
+    }
+    /// Write every page of the chapter in `directory` as `001.jpg`, `002.png`, ...
+    ///
+    /// Returns the written files in reading order.
+    pub async fn download(
+        &self,
+        chapter: &RawKumaChapterData,
+        chapter_url: &Url,
+        directory: impl AsRef<Path>,
+    ) -> RawKumaResult<Vec<PathBuf>> {
+        let directory = directory.as_ref();
+        tokio::fs::create_dir_all(directory).await?;
+        let width = Self::get_file_name_width(Self::get_images(chapter)?.len());
+        let mut files: Vec<(usize, PathBuf)> = Vec::new();
+        let mut stream = Box::pin(self.pages_stream(chapter, chapter_url)?);
+        while let Some(page) = stream.next().await {
+            let page = page?;
+            let path = directory.join(page.file_name(width));
+            tokio::fs::write(&path, &page.data).await?;
+            files.push((page.index, path));
+        }
+        files.sort_by_key(|(index, _)| *index);
+        RawKumaResult::Ok(files.into_iter().map(|(_, path)| path).collect())
+    }
+    /// Fetch the chapter reader page with the downloader's client.
+    pub async fn fetch_chapter(&self, chapter_url: &Url) -> RawKumaResult<RawKumaChapterData> {
+        RawKumaClientFromUrl::chapter(&self.client, chapter_url.clone()).await
+    }
+    /// Fetch the chapter reader page then download its pages in `directory`.
+    ///
+    /// `chapter_slug` converts into a [`ChapterSlug`] as in [`RawKumaClient::chapter`].
+    pub async fn download_slug<S>(
+        &self,
+        chapter_slug: S,
+        directory: impl AsRef<Path>,
+    ) -> RawKumaResult<Vec<PathBuf>>
+    where
+        S: TryInto<ChapterSlug>,
+        Error: From<S::Error>,
+    {
+        let url = self.client.chapter_url(&chapter_slug.try_into()?)?;
+        let chapter = self.fetch_chapter(&url).await?;
+        self.download(&chapter, &url, directory).await
+    }
+}
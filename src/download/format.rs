@@ -0,0 +1,89 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Avif,
+    Bmp,
+}
+
+impl ImageFormat {
+    pub fn extension<'a>(&self) -> &'a str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Avif => "avif",
+            ImageFormat::Bmp => "bmp",
+        }
+    }
+    pub fn mime_type<'a>(&self) -> &'a str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Webp => "image/webp",
+            ImageFormat::Avif => "image/avif",
+            ImageFormat::Bmp => "image/bmp",
+        }
+    }
+    /// Detect the format of a downloaded image from its first bytes, then its
+    /// `Content-Type` header, then the extension of its url.
+    pub fn detect(data: &[u8], content_type: Option<&str>, url: &Url) -> Option<Self> {
+        Self::from_magic_bytes(data)
+            .or_else(|| content_type.and_then(Self::from_mime_type))
+            .or_else(|| {
+                let (_, extension) = url.path_segments()?.next_back()?.rsplit_once('.')?;
+                Self::from_extension(extension)
+            })
+    }
+    /// Detect the format from the first bytes of the file.
+    pub fn from_magic_bytes(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else if data.len() >= 12 && &data[4..8] == b"ftyp" && &data[8..11] == b"avi" {
+            Some(Self::Avif)
+        } else if data.starts_with(b"BM") {
+            Some(Self::Bmp)
+        } else {
+            None
+        }
+    }
+    /// Detect the format from a `Content-Type` header value.
+    pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+        let mime_type = mime_type.split(';').next()?.trim().to_lowercase();
+        match mime_type.as_str() {
+            "image/jpeg" | "image/jpg" => Some(Self::Jpeg),
+            "image/png" => Some(Self::Png),
+            "image/gif" => Some(Self::Gif),
+            "image/webp" => Some(Self::Webp),
+            "image/avif" => Some(Self::Avif),
+            "image/bmp" => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+    /// Detect the format from a file extension.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "gif" => Some(Self::Gif),
+            "webp" => Some(Self::Webp),
+            "avif" => Some(Self::Avif),
+            "bmp" => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+}
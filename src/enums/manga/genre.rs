@@ -2,13 +2,13 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub enum Genre {
+    #[default]
     All,
     Action,
     Adult,
     Adventure,
-    #[cfg_attr(feature = "serde", serde(rename = "Alternative_World"))]
     AlternativeWorld,
     Comedy,
     Drama,
@@ -18,7 +18,6 @@ pub enum Genre {
     Fantasy,
     Food,
     Game,
-    #[cfg_attr(feature = "serde", serde(rename = "Gender_Bender"))]
     GenderBender,
     Harem,
     Historical,
@@ -27,30 +26,23 @@ pub enum Genre {
     Josei,
     Lolicon,
     Magic,
-    #[cfg_attr(feature = "serde", serde(rename = "Martial_Arts"))]
     MartialArts,
     Mature,
     Mecha,
     Medical,
     Mystery,
-    #[cfg_attr(feature = "serde", serde(rename = "N_A"))]
     NA,
     Oneshot,
     Psychological,
     Romance,
-    #[cfg_attr(feature = "serde", serde(rename = "School_Life"))]
     SchoolLife,
-    #[cfg_attr(feature = "serde", serde(rename = "Sci_Fi"))]
     SciFi,
     Seinen,
     Shotacon,
     Shoujo,
-    #[cfg_attr(feature = "serde", serde(rename = "Shoujo_Ai"))]
     ShoujoAi,
     Shounen,
-    #[cfg_attr(feature = "serde", serde(rename = "Shounen_Ai"))]
     ShounenAi,
-    #[cfg_attr(feature = "serde", serde(rename = "Slice_Of_Life"))]
     SliceOfLife,
     Smut,
     Sports,
@@ -61,8 +53,8 @@ pub enum Genre {
     Yaoi,
     Yuri,
 }
-impl<'a> Genre {
-    pub fn as_str(&self) -> &'a str {
+impl Genre {
+    pub fn as_str<'a>(&self) -> &'a str {
         match self {
             Genre::All => "",
             Genre::Action => "action",
@@ -174,9 +166,3 @@ impl<'a> From<&'a str> for Genre {
         }
     }
 }
-
-impl Default for Genre {
-    fn default() -> Self {
-        Self::All
-    }
-}
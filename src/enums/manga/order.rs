@@ -33,8 +33,9 @@ impl<'a> From<&'a str> for Order {
 }
 
 #[cfg_attr(feature = "specta", derive(specta::Type))]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub enum Order {
+    #[default]
     Default,
     AZ,
     ZA,
@@ -42,9 +43,3 @@ pub enum Order {
     Added,
     Popular,
 }
-
-impl Default for Order {
-    fn default() -> Self {
-        Self::Default
-    }
-}
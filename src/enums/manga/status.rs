@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub enum Status {
+    #[default]
     All,
     Ongoing,
     Completed,
@@ -37,9 +38,3 @@ impl<'a> From<&'a str> for Status {
         }
     }
 }
-
-impl Default for Status {
-    fn default() -> Self {
-        Self::All
-    }
-}
@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub enum Type {
+    #[default]
     All,
     Manga,
     Manhwa,
@@ -12,8 +13,8 @@ pub enum Type {
     Novel,
 }
 
-impl<'a> Type {
-    pub fn as_str(&self) -> &'a str {
+impl Type {
+    pub fn as_str<'a>(&self) -> &'a str {
         match self {
             Type::All => "",
             Type::Manga => "manga",
@@ -43,9 +44,3 @@ impl<'a> From<&'a str> for Type {
         }
     }
 }
-
-impl Default for Type {
-    fn default() -> Self {
-        Self::All
-    }
-}
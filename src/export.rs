@@ -0,0 +1,30 @@
+#[cfg(feature = "cbz")]
+pub mod cbz;
+mod comic_info;
+#[cfg(feature = "epub")]
+pub mod epub;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+
+pub use comic_info::ComicInfo;
+
+use crate::{
+    download::{ChapterDownloader, ChapterPage},
+    types::{Chapter, RawKumaResult},
+};
+
+/// A chapter entry with its downloaded pages, in reading order
+#[derive(Clone)]
+pub struct ExportChapter {
+    pub chapter: Chapter,
+    pub pages: Vec<ChapterPage>,
+}
+
+impl ExportChapter {
+    /// Fetch the reader page of `chapter` then download its pages.
+    pub async fn fetch(downloader: &ChapterDownloader, chapter: Chapter) -> RawKumaResult<Self> {
+        let chapter_data = downloader.fetch_chapter(&chapter.url).await?;
+        let pages = downloader.fetch_pages(&chapter_data, &chapter.url).await?;
+        RawKumaResult::Ok(Self { chapter, pages })
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    io::{Cursor, Seek, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    download::{ChapterDownloader, ChapterPage},
+    source::Source,
+    types::{BixboxData, Chapter, RawKumaResult},
+};
+
+use super::ComicInfo;
+
+/// Write `pages` and a `ComicInfo.xml` as a `.cbz` archive.
+///
+/// Images are stored as is since they are already compressed.
+pub fn write_cbz<W: Write + Seek>(
+    writer: W,
+    pages: &[ChapterPage],
+    comic_info: &ComicInfo,
+) -> RawKumaResult<W> {
+    let mut zip = ZipWriter::new(writer);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("ComicInfo.xml", deflated)?;
+    zip.write_all(comic_info.to_xml().as_bytes())?;
+    let width = ChapterDownloader::get_file_name_width(pages.len());
+    for page in pages {
+        zip.start_file(page.file_name(width), stored)?;
+        zip.write_all(&page.data)?;
+    }
+    RawKumaResult::Ok(zip.finish()?)
+}
+
+/// Package chapters as `.cbz` archives with their `ComicInfo.xml`.
+#[derive(Clone)]
+pub struct CbzExporter {
+    downloader: ChapterDownloader,
+    /// Time the chapter lists were fetched, see [`CbzExporter::with_reference_time`]
+    reference_time: Option<DateTime<Utc>>,
+}
+
+impl CbzExporter {
+    pub fn new(downloader: ChapterDownloader) -> Self {
+        Self {
+            downloader,
+            reference_time: None,
+        }
+    }
+    /// Resolve the relative chapter dates against `time`, when the chapter list was fetched,
+    /// instead of the time of the export.
+    pub fn with_reference_time(mut self, time: DateTime<Utc>) -> Self {
+        self.reference_time = Some(time);
+        self
+    }
+    /// Download `chapter` and build the archive in memory.
+    ///
+    /// `data` is the series details the chapter belongs to.
+    pub async fn export_to_vec(
+        &self,
+        data: &BixboxData,
+        chapter: &Chapter,
+    ) -> RawKumaResult<Vec<u8>> {
+        let chapter_data = self.downloader.fetch_chapter(&chapter.url).await?;
+        let pages = self
+            .downloader
+            .fetch_pages(&chapter_data, &chapter.url)
+            .await?;
+        let reference_time = self.reference_time.unwrap_or_else(Utc::now);
+        let comic_info = ComicInfo::new(data, chapter, &reference_time)
+            .with_language_iso(self.downloader.get_client().language())
+            .with_page_count(pages.len());
+        let cursor = write_cbz(Cursor::new(Vec::new()), &pages, &comic_info)?;
+        RawKumaResult::Ok(cursor.into_inner())
+    }
+    /// Download `chapter` and write the archive at `path`.
+    pub async fn export(
+        &self,
+        data: &BixboxData,
+        chapter: &Chapter,
+        path: impl AsRef<Path>,
+    ) -> RawKumaResult<()> {
+        let archive = self.export_to_vec(data, chapter).await?;
+        tokio::fs::write(path, archive).await?;
+        RawKumaResult::Ok(())
+    }
+}
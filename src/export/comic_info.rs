@@ -0,0 +1,120 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Datelike, TimeZone};
+use htmlize::escape_text;
+
+use crate::types::{BixboxData, Chapter};
+
+/// Metadata read by comic servers such as Kavita or Komga,
+/// stored as `ComicInfo.xml` at the root of a `.cbz` archive.
+///
+/// See <https://anansi-project.github.io/docs/comicinfo/intro>
+#[derive(Debug, Clone, Default)]
+pub struct ComicInfo {
+    pub title: Option<String>,
+    pub series: Option<String>,
+    pub number: Option<String>,
+    pub summary: Option<String>,
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+    pub writer: Option<String>,
+    pub genre: Vec<String>,
+    pub web: Option<String>,
+    pub page_count: Option<usize>,
+    pub language_iso: Option<String>,
+    pub manga: Option<String>,
+    /// Rating on a 0 to 5 scale
+    pub community_rating: Option<f32>,
+}
+
+impl ComicInfo {
+    /// Build the metadata of `chapter` from the series details.
+    ///
+    /// `reference` is the time the chapter list was fetched, relative dates such as
+    /// `2 hours ago` being resolved against it.
+    pub fn new<Tz: TimeZone>(
+        data: &BixboxData,
+        chapter: &Chapter,
+        reference: &DateTime<Tz>,
+    ) -> Self {
+        let date = chapter.date.and_then(|date| date.to_naive_date(reference));
+        Self {
+            title: Some(chapter.chapter_num.clone()),
+            series: Some(data.name.clone()),
+            number: Some(chapter.num.to_string()),
+            summary: data.description.clone(),
+            year: date.map(|d| d.year()),
+            month: date.map(|d| d.month()),
+            day: date.map(|d| d.day()),
+            writer: Some(data.get_credited_author().to_string())
+                .filter(|author| !author.is_empty()),
+            genre: data.genres.iter().map(|genre| genre.name.clone()).collect(),
+            web: Some(chapter.url.to_string()),
+            page_count: None,
+            language_iso: None,
+            manga: Some("YesAndRightToLeft".to_string()),
+            community_rating: Self::get_community_rating(data),
+        }
+    }
+    /// Set the language of the scans, such as the [`crate::SourceConfig::language`]
+    pub fn with_language_iso(mut self, language: impl Into<String>) -> Self {
+        self.language_iso = Some(language.into());
+        self
+    }
+    pub fn with_page_count(mut self, page_count: usize) -> Self {
+        self.page_count = Some(page_count);
+        self
+    }
+    /// Scale the series rating to the 0-5 range expected by ComicInfo
+    pub fn get_community_rating(data: &BixboxData) -> Option<f32> {
+        if data.best_rating == 0 || data.rating_count == 0 {
+            return None;
+        }
+        let rating = data.rating_value / f32::from(data.best_rating) * 5.0;
+        Some((rating.clamp(0.0, 5.0) * 10.0).round() / 10.0)
+    }
+    fn write_element(xml: &mut String, name: &str, value: &Option<impl ToString>) {
+        if let Some(value) = value {
+            let _ = writeln!(
+                xml,
+                "  <{name}>{}</{name}>",
+                escape_text(value.to_string().as_str())
+            );
+        }
+    }
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<ComicInfo xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\">\n",
+        );
+        let genre = Some(self.genre.join(", ")).filter(|genre| !genre.is_empty());
+        Self::write_element(&mut xml, "Title", &self.title);
+        Self::write_element(&mut xml, "Series", &self.series);
+        Self::write_element(&mut xml, "Number", &self.number);
+        Self::write_element(&mut xml, "Summary", &self.summary);
+        Self::write_element(&mut xml, "Year", &self.year);
+        Self::write_element(&mut xml, "Month", &self.month);
+        Self::write_element(&mut xml, "Day", &self.day);
+        Self::write_element(&mut xml, "Writer", &self.writer);
+        Self::write_element(&mut xml, "Genre", &genre);
+        Self::write_element(&mut xml, "Web", &self.web);
+        Self::write_element(&mut xml, "PageCount", &self.page_count);
+        Self::write_element(&mut xml, "LanguageISO", &self.language_iso);
+        Self::write_element(&mut xml, "Manga", &self.manga);
+        // The elements follow the order of the `xs:sequence` of the v2.0 schema
+        if let Some(page_count) = self.page_count {
+            xml.push_str("  <Pages>\n");
+            for page in 0..page_count {
+                if page == 0 {
+                    let _ = writeln!(xml, "    <Page Image=\"{page}\" Type=\"FrontCover\" />");
+                } else {
+                    let _ = writeln!(xml, "    <Page Image=\"{page}\" />");
+                }
+            }
+            xml.push_str("  </Pages>\n");
+        }
+        Self::write_element(&mut xml, "CommunityRating", &self.community_rating);
+        xml.push_str("</ComicInfo>\n");
+        xml
+    }
+}
@@ -0,0 +1,472 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Cursor, Seek, Write},
+    ops::RangeBounds,
+    path::Path,
+};
+
+use htmlize::{escape_attribute, escape_text};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    download::{ChapterDownloader, ChapterPage},
+    source::Source,
+    types::{error::Error, manga::RawKumaMangaDetailData, BixboxData, RawKumaResult},
+};
+
+use super::ExportChapter;
+
+/// Viewport used when the size of an image can't be read
+const DEFAULT_DIMENSIONS: (usize, usize) = (800, 1200);
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+fn stored() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Stored)
+}
+
+fn deflated() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+struct EpubItem {
+    id: String,
+    href: String,
+    media_type: String,
+    properties: Option<String>,
+}
+
+fn page_xhtml(title: &str, image_href: &str, (width, height): (usize, usize)) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head>
+  <title>{title}</title>
+  <meta name="viewport" content="width={width}, height={height}"/>
+  <style>html, body {{ margin: 0; padding: 0; }} img {{ display: block; width: {width}px; height: {height}px; }}</style>
+</head>
+<body>
+  <img src="{image_href}" alt=""/>
+</body>
+</html>
+"#,
+        title = escape_text(title),
+        image_href = escape_attribute(image_href),
+    )
+}
+
+fn nav_xhtml(title: &str, entries: &[(String, String)]) -> String {
+    let mut items = String::new();
+    for (label, href) in entries {
+        let _ = writeln!(
+            items,
+            r#"      <li><a href="{}">{}</a></li>"#,
+            escape_attribute(href.as_str()),
+            escape_text(label.as_str())
+        );
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head>
+  <title>{title}</title>
+</head>
+<body>
+  <nav epub:type="toc" id="toc">
+    <h1>{title}</h1>
+    <ol>
+{items}    </ol>
+  </nav>
+</body>
+</html>
+"#,
+        title = escape_text(title),
+    )
+}
+
+/// `name` in lowercase with the runs of other characters than letters and digits
+/// replaced by `-`, and the non-ASCII letters percent-encoded
+fn get_name_key(name: &str) -> String {
+    let mut key = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            key.push(c);
+        } else if c.is_alphanumeric() {
+            let mut buffer = [0; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                let _ = write!(key, "%{:02X}", byte);
+            }
+        } else if !key.is_empty() && !key.ends_with('-') {
+            key.push('-');
+        }
+    }
+    key.trim_end_matches('-').to_string()
+}
+
+/// URN of the book, made of the series name and the range of its chapters,
+/// such as `urn:rawkuma:kanojo-okarishimasu:320.5-321`
+fn get_book_identifier(data: &BixboxData, range: Option<(f32, f32)>) -> String {
+    let mut identifier = format!("urn:rawkuma:{}", get_name_key(data.name.as_str()));
+    if let Some((first, last)) = range {
+        let _ = write!(identifier, ":{}-{}", first, last);
+    }
+    identifier
+}
+
+fn content_opf(
+    data: &BixboxData,
+    identifier: &str,
+    language: &str,
+    manifest: &[EpubItem],
+    spine: &[String],
+) -> String {
+    let mut metadata = String::new();
+    let _ = writeln!(
+        metadata,
+        r#"    <dc:identifier id="book-id">{}</dc:identifier>"#,
+        escape_text(identifier)
+    );
+    let _ = writeln!(
+        metadata,
+        "    <dc:title>{}</dc:title>",
+        escape_text(data.name.as_str())
+    );
+    let _ = writeln!(
+        metadata,
+        "    <dc:language>{}</dc:language>",
+        escape_text(language)
+    );
+    let author = data.get_credited_author();
+    if !author.is_empty() {
+        let _ = writeln!(
+            metadata,
+            "    <dc:creator>{}</dc:creator>",
+            escape_text(author)
+        );
+    }
+    for genre in &data.genres {
+        let _ = writeln!(
+            metadata,
+            "    <dc:subject>{}</dc:subject>",
+            escape_text(genre.name.as_str())
+        );
+    }
+    if let Some(description) = &data.description {
+        let _ = writeln!(
+            metadata,
+            "    <dc:description>{}</dc:description>",
+            escape_text(description.as_str())
+        );
+    }
+    let _ = writeln!(
+        metadata,
+        r#"    <meta property="dcterms:modified">{}</meta>"#,
+        data.date_modified.naive_utc().format("%Y-%m-%dT%H:%M:%SZ")
+    );
+    let mut items = String::new();
+    for item in manifest {
+        let _ = write!(
+            items,
+            r#"    <item id="{}" href="{}" media-type="{}""#,
+            item.id,
+            escape_attribute(item.href.as_str()),
+            item.media_type
+        );
+        if let Some(properties) = &item.properties {
+            let _ = write!(items, r#" properties="{}""#, properties);
+        }
+        items.push_str("/>\n");
+    }
+    let mut itemrefs = String::new();
+    for id in spine {
+        let _ = writeln!(itemrefs, r#"    <itemref idref="{}"/>"#, id);
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" prefix="rendition: http://www.idpf.org/vocab/rendition/#">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+{metadata}    <meta property="rendition:layout">pre-paginated</meta>
+    <meta property="rendition:orientation">portrait</meta>
+    <meta property="rendition:spread">none</meta>
+  </metadata>
+  <manifest>
+{items}  </manifest>
+  <spine page-progression-direction="rtl">
+{itemrefs}  </spine>
+</package>
+"#
+    )
+}
+
+/// Builds a fixed-layout EPUB 3 one chapter at a time, with one page per image.
+///
+/// The images are written to `writer` as soon as a chapter is added, only the
+/// manifest and the navigation entries are kept until [`EpubWriter::finish`].
+pub struct EpubWriter<'a, W: Write + Seek> {
+    zip: ZipWriter<W>,
+    data: &'a BixboxData,
+    language: &'a str,
+    manifest: Vec<EpubItem>,
+    spine: Vec<String>,
+    toc: Vec<(String, String)>,
+    has_cover: bool,
+    chapter_count: usize,
+    first_num: Option<f32>,
+    last_num: Option<f32>,
+}
+
+impl<'a, W: Write + Seek> EpubWriter<'a, W> {
+    /// Start the book, `language` is the language of the scans, such as the [`crate::SourceConfig::language`].
+    pub fn new(writer: W, data: &'a BixboxData, language: &'a str) -> RawKumaResult<Self> {
+        let mut zip = ZipWriter::new(writer);
+        // The mimetype must be the first entry and must not be compressed
+        zip.start_file("mimetype", stored())?;
+        zip.write_all(b"application/epub+zip")?;
+        zip.start_file("META-INF/container.xml", deflated())?;
+        zip.write_all(CONTAINER_XML.as_bytes())?;
+        RawKumaResult::Ok(Self {
+            zip,
+            data,
+            language,
+            manifest: vec![EpubItem {
+                id: "nav".to_string(),
+                href: "nav.xhtml".to_string(),
+                media_type: "application/xhtml+xml".to_string(),
+                properties: Some("nav".to_string()),
+            }],
+            spine: Vec::new(),
+            toc: Vec::new(),
+            has_cover: false,
+            chapter_count: 0,
+            first_num: None,
+            last_num: None,
+        })
+    }
+    /// Whether a cover has been added
+    pub fn has_cover(&self) -> bool {
+        self.has_cover
+    }
+    /// Add the cover page, always first in the reading order.
+    ///
+    /// Only the first cover is kept.
+    pub fn add_cover(&mut self, cover: &ChapterPage) -> RawKumaResult<()> {
+        if self.has_cover {
+            return RawKumaResult::Ok(());
+        }
+        let image_href = format!("images/cover.{}", cover.format.extension());
+        self.zip
+            .start_file(format!("OEBPS/{}", image_href), stored())?;
+        self.zip.write_all(&cover.data)?;
+        self.zip.start_file("OEBPS/xhtml/cover.xhtml", deflated())?;
+        self.zip.write_all(
+            page_xhtml(
+                self.data.name.as_str(),
+                format!("../{}", image_href).as_str(),
+                cover.get_dimensions().unwrap_or(DEFAULT_DIMENSIONS),
+            )
+            .as_bytes(),
+        )?;
+        self.manifest.push(EpubItem {
+            id: "cover-image".to_string(),
+            href: image_href,
+            media_type: cover.format.mime_type().to_string(),
+            properties: Some("cover-image".to_string()),
+        });
+        self.manifest.push(EpubItem {
+            id: "cover".to_string(),
+            href: "xhtml/cover.xhtml".to_string(),
+            media_type: "application/xhtml+xml".to_string(),
+            properties: None,
+        });
+        self.spine.insert(0, "cover".to_string());
+        self.has_cover = true;
+        RawKumaResult::Ok(())
+    }
+    /// Write the pages of `chapter` after the ones already added.
+    pub fn add_chapter(&mut self, chapter: &ExportChapter) -> RawKumaResult<()> {
+        self.chapter_count += 1;
+        self.first_num.get_or_insert(chapter.chapter.num);
+        self.last_num = Some(chapter.chapter.num);
+        for (page_index, page) in chapter.pages.iter().enumerate() {
+            let name = format!("c{:04}_p{:04}", self.chapter_count, page_index + 1);
+            let image_href = format!("images/{}.{}", name, page.format.extension());
+            let page_href = format!("xhtml/{}.xhtml", name);
+            self.zip
+                .start_file(format!("OEBPS/{}", image_href), stored())?;
+            self.zip.write_all(&page.data)?;
+            self.zip
+                .start_file(format!("OEBPS/{}", page_href), deflated())?;
+            self.zip.write_all(
+                page_xhtml(
+                    chapter.chapter.chapter_num.as_str(),
+                    format!("../{}", image_href).as_str(),
+                    page.get_dimensions().unwrap_or(DEFAULT_DIMENSIONS),
+                )
+                .as_bytes(),
+            )?;
+            if page_index == 0 {
+                self.toc
+                    .push((chapter.chapter.chapter_num.clone(), page_href.clone()));
+            }
+            self.manifest.push(EpubItem {
+                id: format!("img_{}", name),
+                href: image_href,
+                media_type: page.format.mime_type().to_string(),
+                properties: None,
+            });
+            self.manifest.push(EpubItem {
+                id: format!("page_{}", name),
+                href: page_href,
+                media_type: "application/xhtml+xml".to_string(),
+                properties: None,
+            });
+            self.spine.push(format!("page_{}", name));
+        }
+        RawKumaResult::Ok(())
+    }
+    /// Write the navigation and package documents then return the underlying writer.
+    ///
+    /// Fails with [`Error::NoChapters`] when the chapters have no page at all.
+    pub fn finish(mut self) -> RawKumaResult<W> {
+        // An empty navigation list is not valid EPUB
+        if self.toc.is_empty() {
+            return RawKumaResult::Err(Error::NoChapters);
+        }
+        let identifier = get_book_identifier(self.data, self.first_num.zip(self.last_num));
+        self.zip.start_file("OEBPS/nav.xhtml", deflated())?;
+        self.zip
+            .write_all(nav_xhtml(self.data.name.as_str(), &self.toc).as_bytes())?;
+        self.zip.start_file("OEBPS/content.opf", deflated())?;
+        self.zip.write_all(
+            content_opf(
+                self.data,
+                identifier.as_str(),
+                self.language,
+                &self.manifest,
+                &self.spine,
+            )
+            .as_bytes(),
+        )?;
+        RawKumaResult::Ok(self.zip.finish()?)
+    }
+}
+
+/// Write a fixed-layout EPUB 3 of chapters already downloaded, see [`EpubWriter`].
+///
+/// The navigation document has one entry per chapter, pointing to its first page.
+/// Fails with [`Error::NoChapters`] when the chapters have no page at all.
+pub fn write_epub<W: Write + Seek>(
+    writer: W,
+    data: &BixboxData,
+    language: &str,
+    cover: Option<&ChapterPage>,
+    chapters: &[ExportChapter],
+) -> RawKumaResult<W> {
+    if chapters.iter().all(|chapter| chapter.pages.is_empty()) {
+        return RawKumaResult::Err(Error::NoChapters);
+    }
+    let mut book = EpubWriter::new(writer, data, language)?;
+    if let Some(cover) = cover {
+        book.add_cover(cover)?;
+    }
+    for chapter in chapters {
+        book.add_chapter(chapter)?;
+    }
+    book.finish()
+}
+
+/// Package a series or a range of its chapters as an EPUB.
+#[derive(Clone)]
+pub struct EpubExporter {
+    downloader: ChapterDownloader,
+}
+
+impl EpubExporter {
+    pub fn new(downloader: ChapterDownloader) -> Self {
+        Self { downloader }
+    }
+    /// Download the cover and the selected chapters and write the EPUB to `writer`,
+    /// one chapter at a time.
+    ///
+    /// An empty range fails with [`Error::NoChapters`] before anything is downloaded.
+    /// When the cover can't be fetched, the first page of the first chapter is used instead.
+    pub async fn export_to_writer<W: Write + Seek>(
+        &self,
+        details: &RawKumaMangaDetailData,
+        range: impl RangeBounds<f32>,
+        writer: W,
+    ) -> RawKumaResult<W> {
+        let selected = details.chapterlist.get_range(range);
+        if selected.is_empty() {
+            return RawKumaResult::Err(Error::NoChapters);
+        }
+        // The cover is served by the same hosts as the chapter pages
+        let referer = details
+            .chapterlist
+            .chapters
+            .first()
+            .map(|chapter| &chapter.url)
+            .unwrap_or(&details.data.image);
+        let cover = self
+            .downloader
+            .fetch_image(&details.data.image, referer)
+            .await
+            .ok();
+        let mut book = EpubWriter::new(
+            writer,
+            &details.data,
+            self.downloader.get_client().language(),
+        )?;
+        if let Some(cover) = &cover {
+            book.add_cover(cover)?;
+        }
+        for chapter in selected {
+            let chapter = ExportChapter::fetch(&self.downloader, chapter).await?;
+            if let (false, Some(page)) = (book.has_cover(), chapter.pages.first()) {
+                book.add_cover(page)?;
+            }
+            book.add_chapter(&chapter)?;
+        }
+        book.finish()
+    }
+    /// Download the cover and the selected chapters then build the EPUB in memory.
+    pub async fn export_to_vec(
+        &self,
+        details: &RawKumaMangaDetailData,
+        range: impl RangeBounds<f32>,
+    ) -> RawKumaResult<Vec<u8>> {
+        let cursor = self
+            .export_to_writer(details, range, Cursor::new(Vec::new()))
+            .await?;
+        RawKumaResult::Ok(cursor.into_inner())
+    }
+    /// Download the selected chapters and write the EPUB at `path`.
+    ///
+    /// The file is removed if the export fails.
+    pub async fn export(
+        &self,
+        details: &RawKumaMangaDetailData,
+        range: impl RangeBounds<f32>,
+        path: impl AsRef<Path>,
+    ) -> RawKumaResult<()> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        let result = match self.export_to_writer(details, range, file).await {
+            Ok(mut file) => file.flush().map_err(Error::from),
+            Err(error) => Err(error),
+        };
+        if result.is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+}
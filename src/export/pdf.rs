@@ -0,0 +1,495 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    ops::RangeBounds,
+    path::Path,
+};
+
+use image::ImageFormat as DecodeFormat;
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Object, ObjectId, Stream, StringFormat,
+};
+
+use crate::{
+    download::{ChapterDownloader, ChapterPage, ImageFormat},
+    types::{error::Error, manga::RawKumaMangaDetailData, BixboxData, RawKumaResult},
+};
+
+use super::ExportChapter;
+
+/// Encode a PDF text string, using UTF-16BE when it isn't plain ASCII
+fn text_string(text: &str) -> Object {
+    if text.is_ascii() {
+        Object::string_literal(text)
+    } else {
+        let mut bytes: Vec<u8> = vec![0xFE, 0xFF];
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+}
+
+/// Largest width or height of a page, the implementation limit of PDF readers
+const MAX_PAGE_SIZE: f32 = 14400.0;
+
+/// What the PDF needs to know about a JPEG to embed it as is
+struct JpegInfo {
+    /// Number of color components, read from the start of frame marker
+    components: u8,
+    /// An Adobe `APP14` marker is present, so CMYK samples are stored inverted
+    is_adobe: bool,
+}
+
+fn read_jpeg_info(data: &[u8]) -> Option<JpegInfo> {
+    let mut is_adobe = false;
+    let mut index = 2;
+    while index + 9 < data.len() {
+        if data[index] != 0xFF {
+            return None;
+        }
+        let marker = data[index + 1];
+        if marker == 0xFF {
+            index += 1;
+            continue;
+        }
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            return data.get(index + 9).map(|components| JpegInfo {
+                components: *components,
+                is_adobe,
+            });
+        }
+        if marker == 0xEE && data[index + 4..].starts_with(b"Adobe") {
+            is_adobe = true;
+        }
+        let length = u16::from_be_bytes([data[index + 2], data[index + 3]]) as usize;
+        index += 2 + length;
+    }
+    None
+}
+
+/// Size of the page showing an image of `width` by `height` pixels, one unit
+/// per pixel, scaled down so that no side is longer than [`MAX_PAGE_SIZE`]
+fn get_page_size(width: u32, height: u32) -> (f32, f32) {
+    let scale = (MAX_PAGE_SIZE / width.max(height) as f32).min(1.0);
+    (width as f32 * scale, height as f32 * scale)
+}
+
+/// Build the image XObject of a page and return it with its size in pixels.
+///
+/// JPEG files are embedded as is, other formats are decoded and stored as compressed RGB.
+/// AVIF can't be decoded and fails with [`Error::UnsupportedImageFormat`].
+fn image_xobject(page: &ChapterPage) -> RawKumaResult<(Stream, u32, u32)> {
+    if page.format == ImageFormat::Jpeg {
+        if let (Some(info), Some((width, height))) =
+            (read_jpeg_info(&page.data), page.get_dimensions())
+        {
+            let color_space = match info.components {
+                1 => "DeviceGray",
+                4 => "DeviceCMYK",
+                _ => "DeviceRGB",
+            };
+            let mut dict = dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => width as i64,
+                "Height" => height as i64,
+                "ColorSpace" => color_space,
+                "BitsPerComponent" => 8,
+                "Filter" => "DCTDecode",
+            };
+            if info.components == 4 && info.is_adobe {
+                // Inverted samples, from 1 to 0 for each of the four components
+                let decode: Vec<Object> = [1, 0, 1, 0, 1, 0, 1, 0].map(Object::from).to_vec();
+                dict.set("Decode", decode);
+            }
+            let stream = Stream::new(dict, page.data.clone());
+            return RawKumaResult::Ok((stream, width as u32, height as u32));
+        }
+    }
+    let format = match page.format {
+        ImageFormat::Jpeg => DecodeFormat::Jpeg,
+        ImageFormat::Png => DecodeFormat::Png,
+        ImageFormat::Gif => DecodeFormat::Gif,
+        ImageFormat::Webp => DecodeFormat::WebP,
+        ImageFormat::Bmp => DecodeFormat::Bmp,
+        ImageFormat::Avif => {
+            return RawKumaResult::Err(Error::UnsupportedImageFormat {
+                url: page.url.clone(),
+                mime_type: page.format.mime_type().to_string(),
+            })
+        }
+    };
+    let image = image::load_from_memory_with_format(&page.data, format)?.to_rgb8();
+    let (width, height) = image.dimensions();
+    let mut stream = Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => width as i64,
+            "Height" => height as i64,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8,
+        },
+        image.into_raw(),
+    );
+    stream.compress()?;
+    RawKumaResult::Ok((stream, width, height))
+}
+
+/// Write `object` in the PDF syntax, streams excepted
+fn write_object(output: &mut Vec<u8>, object: &Object) {
+    match object {
+        Object::Null => output.extend_from_slice(b"null"),
+        Object::Boolean(value) => output.extend_from_slice(value.to_string().as_bytes()),
+        Object::Integer(value) => output.extend_from_slice(value.to_string().as_bytes()),
+        Object::Real(value) => output.extend_from_slice(value.to_string().as_bytes()),
+        Object::Name(name) => {
+            output.push(b'/');
+            for byte in name {
+                if byte.is_ascii_graphic() && !b"#%()/<>[]{}".contains(byte) {
+                    output.push(*byte);
+                } else {
+                    output.extend_from_slice(format!("#{:02X}", byte).as_bytes());
+                }
+            }
+        }
+        Object::String(bytes, StringFormat::Literal) => {
+            output.push(b'(');
+            for byte in bytes {
+                if matches!(byte, b'(' | b')' | b'\\') {
+                    output.push(b'\\');
+                }
+                output.push(*byte);
+            }
+            output.push(b')');
+        }
+        Object::String(bytes, StringFormat::Hexadecimal) => {
+            output.push(b'<');
+            for byte in bytes {
+                output.extend_from_slice(format!("{:02X}", byte).as_bytes());
+            }
+            output.push(b'>');
+        }
+        Object::Array(items) => {
+            output.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(b' ');
+                }
+                write_object(output, item);
+            }
+            output.push(b']');
+        }
+        Object::Dictionary(dict) => {
+            output.extend_from_slice(b"<<");
+            for (key, value) in dict.iter() {
+                write_object(output, &Object::Name(key.clone()));
+                output.push(b' ');
+                write_object(output, value);
+            }
+            output.extend_from_slice(b">>");
+        }
+        Object::Stream(stream) => write_object(output, &Object::Dictionary(stream.dict.clone())),
+        Object::Reference((id, generation)) => {
+            output.extend_from_slice(format!("{} {} R", id, generation).as_bytes())
+        }
+    }
+}
+
+/// Builds a PDF one chapter at a time, with one full-page image per chapter page
+/// and a bookmark per chapter.
+///
+/// The pages are written to `writer` as soon as a chapter is added, only the
+/// object offsets, the page list and the bookmarks are kept until [`PdfWriter::finish`].
+/// Pages are one unit per pixel, scaled down when a side, such as the height of a
+/// webtoon strip, is longer than the 14400 units PDF readers accept.
+pub struct PdfWriter<'a, W: Write> {
+    writer: W,
+    data: &'a BixboxData,
+    position: usize,
+    /// Offset of each object, by object number minus one
+    offsets: Vec<Option<usize>>,
+    pages_id: ObjectId,
+    kids: Vec<Object>,
+    bookmarks: Vec<(String, ObjectId)>,
+}
+
+impl<'a, W: Write> PdfWriter<'a, W> {
+    /// Start the document, its metadata is taken from the series details.
+    pub fn new(writer: W, data: &'a BixboxData) -> RawKumaResult<Self> {
+        let mut pdf = Self {
+            writer,
+            data,
+            position: 0,
+            offsets: Vec::new(),
+            pages_id: (0, 0),
+            kids: Vec::new(),
+            bookmarks: Vec::new(),
+        };
+        // The comment with binary characters tells the file is not plain text
+        pdf.write_all(b"%PDF-1.5\n%\xE2\xE3\xCF\xD3\n")?;
+        pdf.pages_id = pdf.new_object_id();
+        RawKumaResult::Ok(pdf)
+    }
+    fn write_all(&mut self, data: &[u8]) -> RawKumaResult<()> {
+        self.writer.write_all(data)?;
+        self.position += data.len();
+        RawKumaResult::Ok(())
+    }
+    fn new_object_id(&mut self) -> ObjectId {
+        self.offsets.push(None);
+        (self.offsets.len() as u32, 0)
+    }
+    fn write_indirect(&mut self, id: ObjectId, object: &Object) -> RawKumaResult<()> {
+        self.offsets[id.0 as usize - 1] = Some(self.position);
+        let mut output = format!("{} {} obj\n", id.0, id.1).into_bytes();
+        write_object(&mut output, object);
+        if let Object::Stream(stream) = object {
+            output.extend_from_slice(b"\nstream\n");
+            self.write_all(&output)?;
+            self.write_all(&stream.content)?;
+            output = b"\nendstream".to_vec();
+        }
+        output.extend_from_slice(b"\nendobj\n");
+        self.write_all(&output)
+    }
+    fn add_object(&mut self, object: Object) -> RawKumaResult<ObjectId> {
+        let id = self.new_object_id();
+        self.write_indirect(id, &object)?;
+        RawKumaResult::Ok(id)
+    }
+    /// Write the pages of `chapter` after the ones already added.
+    pub fn add_chapter(&mut self, chapter: &ExportChapter) -> RawKumaResult<()> {
+        for (index, page) in chapter.pages.iter().enumerate() {
+            let (mut image, width, height) = image_xobject(page)?;
+            image.dict.set("Length", image.content.len() as i64);
+            let image_id = self.add_object(Object::Stream(image))?;
+            let (width, height) = get_page_size(width, height);
+            let content = Content {
+                operations: vec![
+                    Operation::new("q", vec![]),
+                    Operation::new(
+                        "cm",
+                        vec![
+                            width.into(),
+                            0.into(),
+                            0.into(),
+                            height.into(),
+                            0.into(),
+                            0.into(),
+                        ],
+                    ),
+                    Operation::new("Do", vec!["Im0".into()]),
+                    Operation::new("Q", vec![]),
+                ],
+            };
+            let content = content.encode()?;
+            let content_id = self.add_object(Object::Stream(Stream::new(
+                dictionary! { "Length" => content.len() as i64 },
+                content,
+            )))?;
+            let page_id = self.add_object(Object::Dictionary(dictionary! {
+                "Type" => "Page",
+                "Parent" => self.pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+                "Contents" => content_id,
+                "Resources" => dictionary! {
+                    "XObject" => dictionary! {
+                        "Im0" => image_id,
+                    },
+                },
+            }))?;
+            if index == 0 {
+                self.bookmarks
+                    .push((chapter.chapter.chapter_num.clone(), page_id));
+            }
+            self.kids.push(page_id.into());
+        }
+        RawKumaResult::Ok(())
+    }
+    /// Add one outline item per chapter, pointing to its first page
+    fn write_outline(&mut self) -> RawKumaResult<Option<ObjectId>> {
+        if self.bookmarks.is_empty() {
+            return RawKumaResult::Ok(None);
+        }
+        let bookmarks = std::mem::take(&mut self.bookmarks);
+        let outline_id = self.new_object_id();
+        let ids: Vec<ObjectId> = bookmarks.iter().map(|_| self.new_object_id()).collect();
+        for (index, (title, page_id)) in bookmarks.iter().enumerate() {
+            let mut item = dictionary! {
+                "Title" => text_string(title),
+                "Parent" => outline_id,
+                "Dest" => vec![(*page_id).into(), Object::Name(b"Fit".to_vec())],
+            };
+            if index > 0 {
+                item.set("Prev", ids[index - 1]);
+            }
+            if let Some(next) = ids.get(index + 1) {
+                item.set("Next", *next);
+            }
+            self.write_indirect(ids[index], &Object::Dictionary(item))?;
+        }
+        self.write_indirect(
+            outline_id,
+            &Object::Dictionary(dictionary! {
+                "Type" => "Outlines",
+                "First" => ids[0],
+                "Last" => ids[ids.len() - 1],
+                "Count" => ids.len() as i64,
+            }),
+        )?;
+        RawKumaResult::Ok(Some(outline_id))
+    }
+    /// Write the page tree, the bookmarks, the metadata and the cross-reference
+    /// table then return the underlying writer.
+    ///
+    /// Fails with [`Error::NoChapters`] when the chapters have no page at all.
+    pub fn finish(mut self) -> RawKumaResult<W> {
+        // A document without any page is not valid PDF
+        if self.kids.is_empty() {
+            return RawKumaResult::Err(Error::NoChapters);
+        }
+        let kids = std::mem::take(&mut self.kids);
+        let count = kids.len() as i64;
+        self.write_indirect(
+            self.pages_id,
+            &Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+            }),
+        )?;
+        let mut catalog = dictionary! {
+            "Type" => "Catalog",
+            "Pages" => self.pages_id,
+        };
+        if let Some(outline_id) = self.write_outline()? {
+            catalog.set("Outlines", outline_id);
+            catalog.set("PageMode", "UseOutlines");
+        }
+        let catalog_id = self.add_object(Object::Dictionary(catalog))?;
+        let data = self.data;
+        let mut info = dictionary! {
+            "Title" => text_string(data.name.as_str()),
+            "Creator" => text_string("rawkuma-scraper"),
+            "ModDate" => text_string(data.date_modified.format("D:%Y%m%d%H%M%S").to_string().as_str()),
+        };
+        let author = data.get_credited_author();
+        if !author.is_empty() {
+            info.set("Author", text_string(author));
+        }
+        if let Some(description) = &data.description {
+            info.set("Subject", text_string(description.as_str()));
+        }
+        if !data.genres.is_empty() {
+            let genres: Vec<&str> = data
+                .genres
+                .iter()
+                .map(|genre| genre.name.as_str())
+                .collect();
+            info.set("Keywords", text_string(genres.join(", ").as_str()));
+        }
+        let info_id = self.add_object(Object::Dictionary(info))?;
+
+        let xref_start = self.position;
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f\r\n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            let _ = write!(xref, "{:010} 00000 n\r\n", offset.unwrap_or(0));
+        }
+        let mut trailer = Vec::new();
+        write_object(
+            &mut trailer,
+            &Object::Dictionary(dictionary! {
+                "Size" => self.offsets.len() as i64 + 1,
+                "Root" => catalog_id,
+                "Info" => info_id,
+            }),
+        );
+        self.write_all(xref.as_bytes())?;
+        self.write_all(b"trailer\n")?;
+        self.write_all(&trailer)?;
+        self.write_all(format!("\nstartxref\n{}\n%%EOF\n", xref_start).as_bytes())?;
+        RawKumaResult::Ok(self.writer)
+    }
+}
+
+/// Write a PDF of chapters already downloaded, see [`PdfWriter`].
+///
+/// Fails with [`Error::NoChapters`] when the chapters have no page at all.
+pub fn write_pdf<W: Write>(
+    writer: W,
+    data: &BixboxData,
+    chapters: &[ExportChapter],
+) -> RawKumaResult<W> {
+    if chapters.iter().all(|chapter| chapter.pages.is_empty()) {
+        return RawKumaResult::Err(Error::NoChapters);
+    }
+    let mut pdf = PdfWriter::new(writer, data)?;
+    for chapter in chapters {
+        pdf.add_chapter(chapter)?;
+    }
+    pdf.finish()
+}
+
+/// Package a series or a range of its chapters as a PDF.
+#[derive(Clone)]
+pub struct PdfExporter {
+    downloader: ChapterDownloader,
+}
+
+impl PdfExporter {
+    pub fn new(downloader: ChapterDownloader) -> Self {
+        Self { downloader }
+    }
+    /// Download the chapters whose `num` is in `range` and write the PDF to `writer`,
+    /// one chapter at a time.
+    ///
+    /// An empty range fails with [`Error::NoChapters`] before anything is downloaded.
+    pub async fn export_to_writer<W: Write>(
+        &self,
+        details: &RawKumaMangaDetailData,
+        range: impl RangeBounds<f32>,
+        writer: W,
+    ) -> RawKumaResult<W> {
+        let selected = details.chapterlist.get_range(range);
+        if selected.is_empty() {
+            return RawKumaResult::Err(Error::NoChapters);
+        }
+        let mut pdf = PdfWriter::new(writer, &details.data)?;
+        for chapter in selected {
+            pdf.add_chapter(&ExportChapter::fetch(&self.downloader, chapter).await?)?;
+        }
+        pdf.finish()
+    }
+    /// Download the chapters whose `num` is in `range` then build the PDF in memory.
+    pub async fn export_to_vec(
+        &self,
+        details: &RawKumaMangaDetailData,
+        range: impl RangeBounds<f32>,
+    ) -> RawKumaResult<Vec<u8>> {
+        self.export_to_writer(details, range, Vec::new()).await
+    }
+    /// Download the chapters whose `num` is in `range` then write the PDF at `path`.
+    ///
+    /// The file is removed if the export fails.
+    pub async fn export(
+        &self,
+        details: &RawKumaMangaDetailData,
+        range: impl RangeBounds<f32>,
+        path: impl AsRef<Path>,
+    ) -> RawKumaResult<()> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        let result = match self.export_to_writer(details, range, file).await {
+            Ok(mut file) => file.flush().map_err(Error::from),
+            Err(error) => Err(error),
+        };
+        if result.is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+}
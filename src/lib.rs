@@ -1,7 +1,22 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
 mod client;
 pub mod constant;
+#[cfg(feature = "download")]
+pub mod download;
 pub mod parser;
+pub mod source;
+pub mod transport;
 pub mod types;
-pub use client::RawKumaClient;
+pub use client::{
+    RateLimit, RateLimiter, RawKumaClient, RawKumaClientBuilder, RawKumaClientFromUrl, RetryPolicy,
+    RetryPolicyBuilder,
+};
 pub mod enums;
+#[cfg(any(feature = "cbz", feature = "epub", feature = "pdf"))]
+pub mod export;
+#[cfg(feature = "blocking")]
+pub use blocking::RawKumaBlockingClient;
+pub use source::{Source, SourceConfig, SourceConfigBuilder, SourceRegistry};
 pub use url::Url;
@@ -1,29 +1,174 @@
+use reqwest::Url;
 use scraper::{ElementRef, Html, Selector};
 
-use crate::types::RawKumaResult;
+use self::profile::parse_selector;
+use crate::types::{
+    chapter::RawKumaChapterData,
+    home::RawKumaHomeData,
+    manga::{RawKumaMangaDetailData, RawKumaMangaList},
+    search::RawKumaSearch,
+    FromHtmlParser, ParseContext, ParseMode, Parsed, RawKumaResult,
+};
+
+use self::{
+    chapter::RawKumaChapterParser, home::RawKumaHomeParser, manga::RawKumaMangaListParser,
+    manga_details::RawKumaMangaDetailParser, search::RawKumaSearchParser,
+};
 
 pub mod chapter;
 pub mod home;
 pub mod manga;
 pub mod manga_details;
+pub mod profile;
 pub mod search;
 
+pub use profile::SelectorProfile;
+
 pub trait HtmlParser<'a> {
     fn init(html: &'a Html) -> RawKumaResult<Self>
+    where
+        Self: Sized,
+    {
+        Self::init_with(html, ParseContext::default())
+    }
+    /// Read the page with the selectors of `context`, the items that fail
+    /// to parse being handled following its mode
+    fn init_with(html: &'a Html, context: ParseContext) -> RawKumaResult<Self>
     where
         Self: Sized;
 }
 
-pub fn get_content_selector() -> RawKumaResult<Selector> {
-    RawKumaResult::Ok(Selector::parse("div#content")?)
+pub fn get_content_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+    parse_selector(&profile.content)
 }
 
-pub fn get_content_element(html: &Html) -> RawKumaResult<ElementRef<'_>> {
-    let selector = get_content_selector()?;
+pub fn get_content_element<'a>(
+    html: &'a Html,
+    profile: &SelectorProfile,
+) -> RawKumaResult<ElementRef<'a>> {
+    let selector = get_content_selector(profile)?;
     match html.select(&selector).next() {
-        None => RawKumaResult::Err(crate::types::error::Error::ElementNotFound(String::from(
-            "#content",
-        ))),
+        None => RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
+            profile.content.clone(),
+        )),
         Some(d) => RawKumaResult::Ok(d),
     }
 }
+
+/// Parse the home page from an already fetched HTML document.
+///
+/// `page_url` is the url the document was fetched from,
+/// it is used to resolve the links.
+/// The broken items are skipped as in [`ParseMode::default`], the `_with_mode`
+/// variant fails on them with [`ParseMode::Strict`].
+pub fn parse_home(html: &str, page_url: &Url) -> RawKumaResult<RawKumaHomeData> {
+    RawKumaResult::Ok(parse_home_with_mode(html, page_url, ParseMode::default())?.data)
+}
+
+/// Parse the home page, the broken cards being handled following the mode of `context`.
+pub fn parse_home_with_mode(
+    html: &str,
+    page_url: &Url,
+    context: impl Into<ParseContext>,
+) -> RawKumaResult<Parsed<RawKumaHomeData>> {
+    let html = Html::parse_document(html);
+    let context: ParseContext = context.into().with_page_url(page_url.clone());
+    let parser = RawKumaHomeParser::init_with(&html, context.clone())?;
+    let data = <RawKumaHomeData as FromHtmlParser<RawKumaHomeParser>>::from(parser)?;
+    RawKumaResult::Ok(context.finish(data))
+}
+
+/// Parse a search result page from an already fetched HTML document.
+///
+/// `page_url` is the url the document was fetched from,
+/// it is used to resolve the links and the pagination.
+/// The broken items are skipped as in [`ParseMode::default`], the `_with_mode`
+/// variant fails on them with [`ParseMode::Strict`].
+pub fn parse_search(html: &str, page_url: &Url) -> RawKumaResult<RawKumaSearch> {
+    RawKumaResult::Ok(parse_search_with_mode(html, page_url, ParseMode::default())?.data)
+}
+
+/// Parse a search result page, the broken cards being handled following the mode of `context`.
+pub fn parse_search_with_mode(
+    html: &str,
+    page_url: &Url,
+    context: impl Into<ParseContext>,
+) -> RawKumaResult<Parsed<RawKumaSearch>> {
+    let html = Html::parse_document(html);
+    let context: ParseContext = context.into().with_page_url(page_url.clone());
+    let parser =
+        RawKumaSearchParser::init_with(&html, context.clone())?.with_page_url(page_url.clone());
+    let data = <RawKumaSearch as FromHtmlParser<RawKumaSearchParser>>::from(parser)?;
+    RawKumaResult::Ok(context.finish(data))
+}
+
+/// Parse a `/manga/` listing page from an already fetched HTML document.
+///
+/// `page_url` is the url the document was fetched from,
+/// it is used to resolve the links and the pagination.
+/// The broken items are skipped as in [`ParseMode::default`], the `_with_mode`
+/// variant fails on them with [`ParseMode::Strict`].
+pub fn parse_manga_list(html: &str, page_url: &Url) -> RawKumaResult<RawKumaMangaList> {
+    RawKumaResult::Ok(parse_manga_list_with_mode(html, page_url, ParseMode::default())?.data)
+}
+
+/// Parse a `/manga/` listing page, the broken cards being handled following the mode of `context`.
+pub fn parse_manga_list_with_mode(
+    html: &str,
+    page_url: &Url,
+    context: impl Into<ParseContext>,
+) -> RawKumaResult<Parsed<RawKumaMangaList>> {
+    let html = Html::parse_document(html);
+    let context: ParseContext = context.into().with_page_url(page_url.clone());
+    let parser =
+        RawKumaMangaListParser::init_with(&html, context.clone())?.with_page_url(page_url.clone());
+    let data = <RawKumaMangaList as FromHtmlParser<RawKumaMangaListParser>>::from(parser)?;
+    RawKumaResult::Ok(context.finish(data))
+}
+
+/// Parse a manga details page from an already fetched HTML document.
+///
+/// `page_url` is the url the document was fetched from,
+/// it is used to resolve the links.
+/// The broken items are skipped as in [`ParseMode::default`], the `_with_mode`
+/// variant fails on them with [`ParseMode::Strict`].
+pub fn parse_manga_details(html: &str, page_url: &Url) -> RawKumaResult<RawKumaMangaDetailData> {
+    RawKumaResult::Ok(parse_manga_details_with_mode(html, page_url, ParseMode::default())?.data)
+}
+
+/// Parse a manga details page, the broken chapters, genres and cards
+/// being handled following the mode of `context`.
+pub fn parse_manga_details_with_mode(
+    html: &str,
+    page_url: &Url,
+    context: impl Into<ParseContext>,
+) -> RawKumaResult<Parsed<RawKumaMangaDetailData>> {
+    let html = Html::parse_document(html);
+    let context: ParseContext = context.into().with_page_url(page_url.clone());
+    let parser = RawKumaMangaDetailParser::init_with(&html, context.clone())?;
+    let data = <RawKumaMangaDetailData as FromHtmlParser<RawKumaMangaDetailParser>>::from(parser)?;
+    RawKumaResult::Ok(context.finish(data))
+}
+
+/// Parse a chapter reader page from an already fetched HTML document.
+///
+/// `page_url` is the url the document was fetched from,
+/// it is used to resolve the links.
+/// The broken items are skipped as in [`ParseMode::default`], the `_with_mode`
+/// variant fails on them with [`ParseMode::Strict`].
+pub fn parse_chapter(html: &str, page_url: &Url) -> RawKumaResult<RawKumaChapterData> {
+    RawKumaResult::Ok(parse_chapter_with_mode(html, page_url, ParseMode::default())?.data)
+}
+
+/// Parse a chapter reader page, the broken related cards being handled following the mode of `context`.
+pub fn parse_chapter_with_mode(
+    html: &str,
+    page_url: &Url,
+    context: impl Into<ParseContext>,
+) -> RawKumaResult<Parsed<RawKumaChapterData>> {
+    let html = Html::parse_document(html);
+    let context: ParseContext = context.into().with_page_url(page_url.clone());
+    let parser = RawKumaChapterParser::init_with(&html, context.clone())?;
+    let data = <RawKumaChapterData as FromHtmlParser<RawKumaChapterParser>>::from(parser)?;
+    RawKumaResult::Ok(context.finish(data))
+}
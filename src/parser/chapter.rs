@@ -1,39 +1,46 @@
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 
 use super::{get_content_element, HtmlParser};
+use crate::parser::profile::parse_selector;
 use crate::types::{
     chapter::ts_reader_args::TSReaderArgs, error::Error, BsxTitleData, FromElementRef,
-    RawKumaResult,
+    ParseContext, RawKumaResult,
 };
 
 #[derive(Clone)]
 pub struct RawKumaChapterParser<'a> {
     content: ElementRef<'a>,
+    context: ParseContext,
 }
 
 impl<'a> HtmlParser<'a> for RawKumaChapterParser<'a> {
-    fn init(html: &'a scraper::Html) -> RawKumaResult<Self>
+    fn init_with(html: &'a scraper::Html, context: ParseContext) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
-        let content = get_content_element(html)?;
-        RawKumaResult::Ok(Self { content })
+        let content = get_content_element(html, context.get_selector_profile())?;
+        RawKumaResult::Ok(Self { content, context })
     }
 }
 
 impl<'a> RawKumaChapterParser<'a> {
     pub fn get_related_manga(&self) -> RawKumaResult<Vec<BsxTitleData>> {
-        let bsx_elements = BsxTitleData::get_bsx_elements(&self.content)?;
-        BsxTitleData::from_vec_element(&bsx_elements)
+        let bsx_elements =
+            BsxTitleData::get_bsx_elements(&self.content, self.context.get_selector_profile())?;
+        BsxTitleData::from_vec_element(&bsx_elements, &self.context)
     }
     pub fn get_ts_reader_args(&self) -> RawKumaResult<TSReaderArgs> {
-        let data = TSReaderArgs::get_ts_reader_script_element(&self.content)?;
-        TSReaderArgs::from_element_ref(&data)
+        let data = TSReaderArgs::get_ts_reader_script_element(
+            &self.content,
+            self.context.get_selector_profile(),
+        )?;
+        TSReaderArgs::from_element_ref_with(&data, &self.context)
     }
     pub fn get_entry_title(&self) -> RawKumaResult<String> {
-        let h1_selector = Selector::parse("h1")?;
+        let profile = self.context.get_selector_profile();
+        let h1_selector = parse_selector(&profile.reader.title)?;
         match self.content.select(&h1_selector).next() {
-            None => RawKumaResult::Err(Error::ElementNotFound("h1".to_string())),
+            None => RawKumaResult::Err(Error::ElementNotFound(profile.reader.title.clone())),
             Some(title) => {
                 let titles: Vec<&str> = title.text().collect();
                 let title: String = titles.concat();
@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use derive_builder::Builder;
 use scraper::{ElementRef, Html, Selector};
 
-use crate::types::{error::Error, BsxTitleData, FromElementRef, RawKumaResult, UtaoTitleData};
+use crate::parser::{profile::parse_selector, SelectorProfile};
+use crate::types::{
+    error::Error, BsxTitleData, FromElementRef, ParseContext, RawKumaResult, UtaoTitleData,
+};
 
 use super::HtmlParser;
 
@@ -15,60 +18,97 @@ pub struct RawKumaHomeParser<'a> {
     recommandation: HashMap<String, Vec<ElementRef<'a>>>,
     #[builder(setter(skip = true))]
     utao_elements: Vec<ElementRef<'a>>,
+    #[builder(setter(skip = true))]
+    context: ParseContext,
 }
 
 impl<'a> HtmlParser<'a> for RawKumaHomeParser<'a> {
-    fn init(html: &'a Html) -> RawKumaResult<Self> {
-        let popular_today = Self::find_popular_today_elements(html)?;
-        let recommandation = Self::find_recomendation_elements(html)?;
-        let utaos = Self::get_utao_divs(html)?;
+    fn init_with(html: &'a Html, context: ParseContext) -> RawKumaResult<Self> {
+        let profile = context.get_selector_profile();
+        let popular_today = Self::find_popular_today_elements(html, profile)?;
+        let recommandation = Self::find_recomendation_elements(html, profile)?;
+        let utaos = Self::get_utao_divs(html, profile)?;
         RawKumaResult::Ok(Self {
             popular_today,
             recommandation,
             utao_elements: utaos,
+            context,
         })
     }
 }
 
 impl<'a> RawKumaHomeParser<'a> {
-    pub fn div_listupd_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[class="listupd"]"#)?)
+    pub fn div_listupd_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.home.listupd)
     }
 
-    pub fn get_div_listupd(html: &Html) -> RawKumaResult<Vec<ElementRef>> {
-        let divs: Vec<ElementRef> = html.select(&(Self::div_listupd_selector()?)).collect();
+    pub fn get_div_listupd(
+        html: &'a Html,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<ElementRef<'a>>> {
+        let divs: Vec<ElementRef> = html
+            .select(&(Self::div_listupd_selector(profile)?))
+            .collect();
         RawKumaResult::Ok(divs)
     }
 
-    pub fn div_bixbox_hothome_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[class="bixbox hothome"]"#)?)
+    pub fn div_bixbox_hothome_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.home.hothome)
     }
 
-    pub fn get_div_bixbox_hothome(html: &'a Html) -> RawKumaResult<ElementRef<'a>> {
-        let op: Vec<ElementRef> = html.select(&(Self::div_listupd_selector()?)).collect();
-        match op.first() {
-            None => RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
-                "div[class='bixbox hothome']".to_string(),
-            )),
-            Some(d) => RawKumaResult::Ok(*d),
+    /// The card list of the "Popular Today" block
+    pub fn get_div_bixbox_hothome(
+        html: &'a Html,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let hothome = match html
+            .select(&(Self::div_bixbox_hothome_selector(profile)?))
+            .next()
+        {
+            None => {
+                return RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
+                    profile.home.hothome.clone(),
+                ))
+            }
+            Some(d) => d,
+        };
+        match hothome
+            .select(&(Self::div_listupd_selector(profile)?))
+            .next()
+        {
+            None => RawKumaResult::Err(crate::types::error::Error::ElementNotFoundInNested {
+                element: profile.home.listupd.clone(),
+                parent: profile.home.hothome.clone(),
+            }),
+            Some(d) => RawKumaResult::Ok(d),
         }
     }
 
-    pub fn find_popular_today_elements(html: &'a Html) -> RawKumaResult<Vec<ElementRef<'a>>> {
-        let div = Self::get_div_bixbox_hothome(html)?;
-        let bsx_elements: Vec<ElementRef> =
-            div.select(&(BsxTitleData::div_bsx_selector()?)).collect();
+    pub fn find_popular_today_elements(
+        html: &'a Html,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<ElementRef<'a>>> {
+        let div = Self::get_div_bixbox_hothome(html, profile)?;
+        let bsx_elements: Vec<ElementRef> = div
+            .select(&(BsxTitleData::div_bsx_selector(profile)?))
+            .collect();
         RawKumaResult::Ok(bsx_elements)
     }
 
-    pub fn div_series_gen_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[class="series-gen"]"#)?)
+    pub fn div_series_gen_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.home.series_gen)
     }
 
-    pub fn get_div_series_gen(html: &'a Html) -> RawKumaResult<ElementRef<'a>> {
-        match html.select(&(Self::div_series_gen_selector()?)).next() {
+    pub fn get_div_series_gen(
+        html: &'a Html,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        match html
+            .select(&(Self::div_series_gen_selector(profile)?))
+            .next()
+        {
             None => RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
-                r#"div[class="series-gen"]"#.to_string(),
+                profile.home.series_gen.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
@@ -76,41 +116,54 @@ impl<'a> RawKumaHomeParser<'a> {
 
     pub fn find_recomendation_elements(
         html: &'a Html,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<HashMap<String, Vec<ElementRef<'a>>>> {
-        let recomm = Self::get_recommendation_themes(html)?;
+        let recomm = Self::get_recommendation_themes(html, profile)?;
         let mut data: HashMap<String, Vec<ElementRef<'a>>> = HashMap::new();
         for (key, elememt) in recomm {
             data.insert(
                 key,
                 elememt
-                    .select(&(BsxTitleData::div_bsx_selector()?))
+                    .select(&(BsxTitleData::div_bsx_selector(profile)?))
                     .collect(),
             );
         }
         RawKumaResult::Ok(data)
     }
 
-    pub fn div_nav_tabs_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"ul[class="nav-tabs"]"#)?)
+    pub fn div_nav_tabs_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.home.nav_tabs)
     }
 
-    pub fn get_div_nav_tabs(html: &'a ElementRef) -> RawKumaResult<ElementRef<'a>> {
-        match html.select(&(Self::div_series_gen_selector()?)).next() {
+    pub fn get_div_nav_tabs(
+        html: &'a ElementRef,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        match html
+            .select(&(Self::div_series_gen_selector(profile)?))
+            .next()
+        {
             None => RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
-                r#"ul[class="nav-tabs"]"#.to_string(),
+                profile.home.nav_tabs.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
 
-    pub fn get_nav_serie_gen_divs(html: &'a Html) -> RawKumaResult<ElementRef<'a>> {
-        match html.select(&(Self::div_series_gen_selector()?)).next() {
+    pub fn get_nav_serie_gen_divs(
+        html: &'a Html,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        match html
+            .select(&(Self::div_series_gen_selector(profile)?))
+            .next()
+        {
             None => RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
-                r#"div[class="series-gen"]"#.to_string(),
+                profile.home.series_gen.clone(),
             )),
-            Some(d) => match d.select(&(Self::div_nav_tabs_selector()?)).next() {
+            Some(d) => match d.select(&(Self::div_nav_tabs_selector(profile)?)).next() {
                 None => RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
-                    r#"ul[class="nav-tabs"]"#.to_string(),
+                    profile.home.nav_tabs.clone(),
                 )),
                 Some(d) => RawKumaResult::Ok(d),
             },
@@ -119,11 +172,12 @@ impl<'a> RawKumaHomeParser<'a> {
 
     pub fn get_recommendation_themes(
         html: &'a Html,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<HashMap<String, ElementRef<'a>>> {
         let mut refs: HashMap<String, ElementRef<'a>> = HashMap::new();
-        let a = "a".to_string();
-        let selector = Selector::parse("a")?;
-        for theme in (Self::get_nav_serie_gen_divs(html)?).select(&selector) {
+        let a = profile.home.nav_tab.clone();
+        let selector = parse_selector(&profile.home.nav_tab)?;
+        for theme in (Self::get_nav_serie_gen_divs(html, profile)?).select(&selector) {
             let text = theme
                 .text()
                 .next()
@@ -152,52 +206,36 @@ impl<'a> RawKumaHomeParser<'a> {
         RawKumaResult::Ok(refs)
     }
 
-    pub fn div_utao_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[class="utao"]"#)?)
+    pub fn div_utao_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.home.utao)
     }
 
-    pub fn get_utao_divs(html: &'a Html) -> RawKumaResult<Vec<ElementRef<'a>>> {
+    pub fn get_utao_divs(
+        html: &'a Html,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<ElementRef<'a>>> {
         RawKumaResult::Ok(
-            html.select(&(Self::div_utao_selector()?))
+            html.select(&(Self::div_utao_selector(profile)?))
                 .collect::<Vec<ElementRef<'a>>>(),
         )
     }
 
-    pub fn get_popular_today(&self) -> Vec<BsxTitleData> {
-        let mut result: Vec<BsxTitleData> = Vec::new();
-        for element in &self.popular_today {
-            /*
-
-
-            match BsxTitleData::from_element_ref(*element) {
-                RawKumaResult::Ok(d) => {
-                    result.push(d);
-                }
-                _ => {}
-            }
-            */
-            result.push(BsxTitleData::from_element_ref(element).unwrap())
-        }
-        result
+    pub fn get_popular_today(&self) -> RawKumaResult<Vec<BsxTitleData>> {
+        BsxTitleData::from_vec_element(&self.popular_today, &self.context)
     }
 
-    pub fn get_recommandation(&self) -> HashMap<String, Vec<BsxTitleData>> {
+    pub fn get_recommandation(&self) -> RawKumaResult<HashMap<String, Vec<BsxTitleData>>> {
         let mut result: HashMap<String, Vec<BsxTitleData>> = HashMap::new();
         for (title, elements) in &self.recommandation {
-            if let RawKumaResult::Ok(d) = BsxTitleData::from_vec_element(elements) {
-                result.insert(title.clone(), d);
-            }
+            result.insert(
+                title.clone(),
+                BsxTitleData::from_vec_element(elements, &self.context)?,
+            );
         }
-        result
+        RawKumaResult::Ok(result)
     }
 
-    pub fn get_latest(&self) -> Vec<UtaoTitleData> {
-        let mut result: Vec<UtaoTitleData> = Vec::new();
-        for element in &self.utao_elements {
-            if let RawKumaResult::Ok(d) = UtaoTitleData::from_element_ref(element) {
-                result.push(d);
-            }
-        }
-        result
+    pub fn get_latest(&self) -> RawKumaResult<Vec<UtaoTitleData>> {
+        UtaoTitleData::from_vec_element(&self.utao_elements, &self.context)
     }
 }
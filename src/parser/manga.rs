@@ -1,19 +1,57 @@
+use reqwest::Url;
 use scraper::ElementRef;
 
 use super::{get_content_element, HtmlParser};
-use crate::types::RawKumaResult;
+use crate::{
+    constant::BASE_URL,
+    types::{BsxTitleData, FromElementRef, Pagination, ParseContext, RawKumaResult},
+};
 
 #[derive(Clone)]
-pub struct RawKumaMangaDetailParser<'a> {
+pub struct RawKumaMangaListParser<'a> {
     content: ElementRef<'a>,
+    page_url: Url,
+    context: ParseContext,
 }
 
-impl<'a> HtmlParser<'a> for RawKumaMangaDetailParser<'a> {
-    fn init(html: &'a scraper::Html) -> RawKumaResult<Self>
+/// Former name of [`RawKumaMangaListParser`], the series details have their own
+/// [`super::manga_details::RawKumaMangaDetailParser`]
+#[deprecated(note = "renamed to `RawKumaMangaListParser`")]
+pub type RawKumaMangaDetailParser<'a> = RawKumaMangaListParser<'a>;
+
+impl<'a> HtmlParser<'a> for RawKumaMangaListParser<'a> {
+    fn init_with(html: &'a scraper::Html, context: ParseContext) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
-        let content = get_content_element(html)?;
-        RawKumaResult::Ok(Self { content })
+        let content = get_content_element(html, context.get_selector_profile())?;
+        let page_url = Url::parse(BASE_URL)?.join("manga/")?;
+        RawKumaResult::Ok(Self {
+            content,
+            page_url,
+            context,
+        })
+    }
+}
+
+impl<'a> RawKumaMangaListParser<'a> {
+    /// Set the url the page was fetched from.
+    ///
+    /// The pagination links of the listing are relative, they are resolved against this url.
+    pub fn with_page_url(mut self, page_url: Url) -> Self {
+        self.page_url = page_url;
+        self
+    }
+    pub fn get_bsx_results(&'a self) -> RawKumaResult<Vec<BsxTitleData>> {
+        let elements: Vec<ElementRef<'a>> =
+            BsxTitleData::get_bsx_elements(&self.content, self.context.get_selector_profile())?;
+        BsxTitleData::from_vec_element(&elements, &self.context)
+    }
+    pub fn get_pagination(&'a self) -> RawKumaResult<Pagination> {
+        Pagination::from_hpage(
+            &self.content,
+            &self.page_url,
+            self.context.get_selector_profile(),
+        )
     }
 }
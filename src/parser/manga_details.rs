@@ -1,49 +1,56 @@
 use scraper::ElementRef;
 
 use super::{get_content_element, HtmlParser};
-use crate::types::{BixboxData, BsxTitleData, ChapterList, FromElementRef, RawKumaResult};
+use crate::types::{
+    BixboxData, BsxTitleData, ChapterList, FromElementRef, ParseContext, RawKumaResult,
+};
 
 #[derive(Clone)]
 pub struct RawKumaMangaDetailParser<'a> {
     content: ElementRef<'a>,
+    context: ParseContext,
 }
 
 impl<'a> HtmlParser<'a> for RawKumaMangaDetailParser<'a> {
-    fn init(html: &'a scraper::Html) -> RawKumaResult<Self>
+    fn init_with(html: &'a scraper::Html, context: ParseContext) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
-        let content = get_content_element(html)?;
-        RawKumaResult::Ok(Self { content })
+        let content = get_content_element(html, context.get_selector_profile())?;
+        RawKumaResult::Ok(Self { content, context })
     }
 }
 
 impl<'a> RawKumaMangaDetailParser<'a> {
     pub fn get_bixbox_data(&self) -> RawKumaResult<BixboxData> {
+        let profile = self.context.get_selector_profile();
         let bixbox = match self
             .content
             .clone()
-            .select(&(BixboxData::get_bix_box_anime_full_selector()?))
+            .select(&(BixboxData::get_bix_box_anime_full_selector(profile)?))
             .next()
         {
             None => {
                 return RawKumaResult::Err(crate::types::error::Error::ElementNotFound(
-                    "div.bixbox.animefull".to_string(),
+                    profile.details.bixbox.clone(),
                 ))
             }
             Some(d) => d,
         };
-        BixboxData::from_element_ref(&bixbox)
+        BixboxData::from_element_ref_with(&bixbox, &self.context)
     }
     pub fn get_chapter_list(&self) -> RawKumaResult<ChapterList> {
-        let chapter_list = ChapterList::get_chapter_list_element(&self.content)?;
-        ChapterList::from_element_ref(&chapter_list)
+        let chapter_list = ChapterList::get_chapter_list_element(
+            &self.content,
+            self.context.get_selector_profile(),
+        )?;
+        ChapterList::from_element_ref_with(&chapter_list, &self.context)
     }
     pub fn get_related_series(&self) -> RawKumaResult<Vec<BsxTitleData>> {
         let bsx_elements: Vec<ElementRef<'a>> = self
             .content
-            .select(&(BsxTitleData::div_bsx_selector()?))
+            .select(&(BsxTitleData::div_bsx_selector(self.context.get_selector_profile())?))
             .collect();
-        BsxTitleData::from_vec_element(&bsx_elements)
+        BsxTitleData::from_vec_element(&bsx_elements, &self.context)
     }
 }
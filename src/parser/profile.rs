@@ -0,0 +1,370 @@
+use std::path::Path;
+
+use scraper::Selector;
+use serde::{Deserialize, Serialize};
+
+use crate::types::RawKumaResult;
+
+/// The CSS selectors used by the parsers.
+///
+/// The default profile matches the rawkuma pages. A profile can be loaded from
+/// JSON, or TOML with the `toml` feature, every missing selector keeping its
+/// default value, so a config file only needs the selectors to fix:
+///
+/// ```toml
+/// [chapter_list]
+/// download = "a.download"
+/// ```
+///
+/// The parsers read the profile of their [`crate::types::ParseContext`],
+/// see [`crate::types::ParseContext::with_selector_profile`].
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct SelectorProfile {
+    /// Main column of every page
+    pub content: String,
+    pub home: HomeSelectors,
+    pub bsx: BsxSelectors,
+    pub utao: UtaoSelectors,
+    pub details: DetailsSelectors,
+    pub chapter_list: ChapterListSelectors,
+    pub genres: GenreSelectors,
+    pub pagination: PaginationSelectors,
+    pub reader: ReaderSelectors,
+    /// Texts of the labels, not selectors
+    pub labels: InfoLabels,
+}
+
+impl Default for SelectorProfile {
+    fn default() -> Self {
+        Self {
+            content: "div#content".to_string(),
+            home: Default::default(),
+            bsx: Default::default(),
+            utao: Default::default(),
+            details: Default::default(),
+            chapter_list: Default::default(),
+            genres: Default::default(),
+            pagination: Default::default(),
+            reader: Default::default(),
+            labels: Default::default(),
+        }
+    }
+}
+
+/// Sections of the home page
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct HomeSelectors {
+    pub listupd: String,
+    /// Block of the popular titles, holding their `listupd`
+    pub hothome: String,
+    pub series_gen: String,
+    pub nav_tabs: String,
+    /// Tab of `nav_tabs`, its `href` being the selector of the tab content
+    pub nav_tab: String,
+    pub utao: String,
+}
+
+impl Default for HomeSelectors {
+    fn default() -> Self {
+        Self {
+            listupd: r#"div[class="listupd"]"#.to_string(),
+            hothome: "div.bixbox.hothome".to_string(),
+            series_gen: r#"div[class="series-gen"]"#.to_string(),
+            nav_tabs: r#"ul[class="nav-tabs"]"#.to_string(),
+            nav_tab: "a".to_string(),
+            utao: r#"div[class="utao"]"#.to_string(),
+        }
+    }
+}
+
+/// Series cards of the search results, listings and related series
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct BsxSelectors {
+    pub card: String,
+    pub link: String,
+    pub image: String,
+    pub rating: String,
+}
+
+impl Default for BsxSelectors {
+    fn default() -> Self {
+        Self {
+            card: r#"div[class="bsx"]"#.to_string(),
+            link: "a".to_string(),
+            image: "img".to_string(),
+            rating: r#"div[class="numscore"]"#.to_string(),
+        }
+    }
+}
+
+/// Latest updates of the home page
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct UtaoSelectors {
+    pub image_box: String,
+    pub image: String,
+    pub series_link: String,
+    pub chapters: String,
+    pub chapter: String,
+    pub chapter_link: String,
+}
+
+impl Default for UtaoSelectors {
+    fn default() -> Self {
+        Self {
+            image_box: r#"div[class="imgu"]"#.to_string(),
+            image: "img".to_string(),
+            series_link: r#"a[class="series"]"#.to_string(),
+            chapters: r#"ul[class="Manga"]"#.to_string(),
+            chapter: "li".to_string(),
+            chapter_link: "a".to_string(),
+        }
+    }
+}
+
+/// Series information of the manga details page
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct DetailsSelectors {
+    pub bixbox: String,
+    pub image: String,
+    pub name: String,
+    pub worst_rating: String,
+    pub best_rating: String,
+    pub rating_count: String,
+    pub rating_value: String,
+    pub author: String,
+    pub author_name: String,
+    pub description: String,
+    pub date_modified: String,
+    pub date_published: String,
+    /// Row of the info table, a `label` followed by a `value`
+    pub info_row: String,
+    pub info_row_label: String,
+    pub info_row_value: String,
+    /// `Status` and `Type` entries
+    pub imptdt: String,
+    pub imptdt_value: String,
+    pub followers: String,
+}
+
+impl Default for DetailsSelectors {
+    fn default() -> Self {
+        Self {
+            bixbox: "div.bixbox.animefull".to_string(),
+            image: r#"img[itemprop="image"]"#.to_string(),
+            name: r#"h1[itemprop="name"]"#.to_string(),
+            worst_rating: r#"meta[itemprop="worstRating"]"#.to_string(),
+            best_rating: r#"meta[itemprop="bestRating"]"#.to_string(),
+            rating_count: r#"meta[itemprop="ratingCount"]"#.to_string(),
+            rating_value: r#"div[itemprop="ratingValue"]"#.to_string(),
+            author: r#"span[itemprop="author"]"#.to_string(),
+            author_name: r#"i[itemprop="name"]"#.to_string(),
+            description: r#"div[itemprop="description"]"#.to_string(),
+            date_modified: r#"time[itemprop="dateModified"]"#.to_string(),
+            date_published: r#"time[itemprop="datePublished"]"#.to_string(),
+            info_row: "div.fmed, div.wd-full".to_string(),
+            info_row_label: "b".to_string(),
+            info_row_value: "span".to_string(),
+            imptdt: "div.tsinfo div.imptdt".to_string(),
+            imptdt_value: "i, a".to_string(),
+            followers: "div.bmc".to_string(),
+        }
+    }
+}
+
+/// Chapter list of the manga details page
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ChapterListSelectors {
+    pub list: String,
+    pub chapter: String,
+    pub eph_num: String,
+    pub link: String,
+    pub download: String,
+    pub number: String,
+    pub date: String,
+}
+
+impl Default for ChapterListSelectors {
+    fn default() -> Self {
+        Self {
+            list: "div#chapterlist".to_string(),
+            chapter: "li[data-num]".to_string(),
+            eph_num: "div.eph-num".to_string(),
+            link: "a".to_string(),
+            download: "a.dload".to_string(),
+            number: ".chapternum".to_string(),
+            date: ".chapterdate".to_string(),
+        }
+    }
+}
+
+/// Genres of the manga details page
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct GenreSelectors {
+    pub container: String,
+    pub tag: String,
+}
+
+impl Default for GenreSelectors {
+    fn default() -> Self {
+        Self {
+            container: ".mgen".to_string(),
+            tag: r#"a[rel="tag"]"#.to_string(),
+        }
+    }
+}
+
+/// Pagination of the search results and listings
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct PaginationSelectors {
+    /// Previous and next links of the listings
+    pub hpage: String,
+    pub hpage_next: String,
+    pub hpage_previous: String,
+    /// Numbered pagination of the search results
+    pub pagination: String,
+    pub page_numbers: String,
+}
+
+impl Default for PaginationSelectors {
+    fn default() -> Self {
+        Self {
+            hpage: "div.hpage".to_string(),
+            hpage_next: "a.r".to_string(),
+            hpage_previous: "a.l".to_string(),
+            pagination: "div.pagination".to_string(),
+            page_numbers: ".page-numbers".to_string(),
+        }
+    }
+}
+
+/// Chapter reader page
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReaderSelectors {
+    pub title: String,
+    /// Script calling `ts_reader.run` with the image sources
+    pub script: String,
+}
+
+impl Default for ReaderSelectors {
+    fn default() -> Self {
+        Self {
+            title: "h1".to_string(),
+            script: "div.readingnav.rnavbot + script".to_string(),
+        }
+    }
+}
+
+/// Labels of the info table rows and of the `Status` and `Type` entries of the
+/// series page, matched against their trimmed text
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct InfoLabels {
+    pub alternative_titles: String,
+    pub author: String,
+    pub artist: String,
+    pub serialization: String,
+    pub released: String,
+    pub views: String,
+    pub status: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+impl Default for InfoLabels {
+    fn default() -> Self {
+        Self {
+            alternative_titles: "Alternative Titles".to_string(),
+            author: "Author".to_string(),
+            artist: "Artist".to_string(),
+            serialization: "Serialization".to_string(),
+            released: "Released".to_string(),
+            views: "Views".to_string(),
+            status: "Status".to_string(),
+            type_: "Type".to_string(),
+        }
+    }
+}
+
+impl SelectorProfile {
+    /// The selectors of the rawkuma pages
+    pub fn rawkuma() -> Self {
+        Self::default()
+    }
+    /// Read a profile from JSON, the missing selectors keeping their default value
+    pub fn from_json(json: &str) -> RawKumaResult<Self> {
+        let profile: Self = serde_json::from_str(json)?;
+        profile.validate()?;
+        RawKumaResult::Ok(profile)
+    }
+    /// Read a profile from TOML, the missing selectors keeping their default value
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> RawKumaResult<Self> {
+        let profile: Self = toml::from_str(toml)?;
+        profile.validate()?;
+        RawKumaResult::Ok(profile)
+    }
+    /// Read a profile from a `.json` file, or a `.toml` file with the `toml` feature
+    pub fn from_path(path: impl AsRef<Path>) -> RawKumaResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml(&content),
+            _ => Self::from_json(&content),
+        }
+    }
+    /// Check that every selector is valid CSS, the labels being plain text
+    pub fn validate(&self) -> RawKumaResult<()> {
+        fn validate_value(value: &serde_json::Value) -> RawKumaResult<()> {
+            match value {
+                serde_json::Value::String(selector) => {
+                    Selector::parse(selector)?;
+                }
+                serde_json::Value::Object(fields) => {
+                    fields.values().try_for_each(validate_value)?;
+                }
+                _ => {}
+            }
+            RawKumaResult::Ok(())
+        }
+        let mut value = serde_json::to_value(self)?;
+        if let Some(sections) = value.as_object_mut() {
+            sections.remove("labels");
+        }
+        validate_value(&value)
+    }
+}
+
+/// Parse a selector of a [`SelectorProfile`]
+pub fn parse_selector(selector: &str) -> RawKumaResult<Selector> {
+    RawKumaResult::Ok(Selector::parse(selector)?)
+}
@@ -1,26 +1,52 @@
+use reqwest::Url;
 use scraper::ElementRef;
 
 use super::{get_content_element, HtmlParser};
-use crate::types::{BsxTitleData, FromElementRef, RawKumaResult};
+use crate::{
+    constant::BASE_URL,
+    types::{BsxTitleData, FromElementRef, Pagination, ParseContext, RawKumaResult},
+};
 
 #[derive(Clone)]
 pub struct RawKumaSearchParser<'a> {
     content: ElementRef<'a>,
+    page_url: Url,
+    context: ParseContext,
 }
 
 impl<'a> HtmlParser<'a> for RawKumaSearchParser<'a> {
-    fn init(html: &'a scraper::Html) -> RawKumaResult<Self>
+    fn init_with(html: &'a scraper::Html, context: ParseContext) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
-        let content = get_content_element(html)?;
-        RawKumaResult::Ok(Self { content })
+        let content = get_content_element(html, context.get_selector_profile())?;
+        let page_url = Url::parse(BASE_URL)?;
+        RawKumaResult::Ok(Self {
+            content,
+            page_url,
+            context,
+        })
     }
 }
 
 impl<'a> RawKumaSearchParser<'a> {
+    /// Set the url the page was fetched from.
+    ///
+    /// It is used to resolve the pagination links and to know the current page.
+    pub fn with_page_url(mut self, page_url: Url) -> Self {
+        self.page_url = page_url;
+        self
+    }
     pub fn get_bsx_results(&'a self) -> RawKumaResult<Vec<BsxTitleData>> {
-        let elements: Vec<ElementRef<'a>> = BsxTitleData::get_bsx_elements(&self.content)?;
-        BsxTitleData::from_vec_element(&elements)
+        let elements: Vec<ElementRef<'a>> =
+            BsxTitleData::get_bsx_elements(&self.content, self.context.get_selector_profile())?;
+        BsxTitleData::from_vec_element(&elements, &self.context)
+    }
+    pub fn get_pagination(&'a self) -> RawKumaResult<Pagination> {
+        Pagination::from_page_numbers(
+            &self.content,
+            &self.page_url,
+            self.context.get_selector_profile(),
+        )
     }
 }
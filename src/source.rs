@@ -0,0 +1,143 @@
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use derive_builder::Builder;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constant::BASE_URL,
+    parser::SelectorProfile,
+    types::{
+        chapter::RawKumaChapterData,
+        home::RawKumaHomeData,
+        manga::{MangaListParameter, RawKumaMangaDetailData, RawKumaMangaList},
+        search::RawKumaSearch,
+        ParseContext, ParseMode, RawKumaResult,
+    },
+};
+
+mod registry;
+
+pub use registry::{
+    normalize_title, SourceFailure, SourceRegistry, SourcedTitle, UnifiedSearch, UnifiedTitle,
+    DEFAULT_SOURCE_TIMEOUT,
+};
+
+/// Settings of a site built on the WordPress "MangaReader" theme, such as rawkuma.
+///
+/// Can be read from JSON, or TOML with the `toml` feature:
+///
+/// ```toml
+/// id = "example"
+/// name = "Example Raw"
+/// base_url = "https://raw.example.org/"
+/// language = "ja"
+/// date_format = "%d.%m.%Y"
+///
+/// [selector_profile.chapter_list]
+/// download = "a.download"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Builder)]
+#[builder(build_fn(error = "crate::types::error::BuilderError"))]
+pub struct SourceConfig {
+    /// Short unique name, such as `rawkuma`
+    #[builder(setter(into))]
+    pub id: String,
+    /// Name to display
+    #[builder(setter(into))]
+    pub name: String,
+    pub base_url: Url,
+    /// ISO 639-1 code of the language of the scans, such as `ja`
+    #[builder(setter(into))]
+    pub language: String,
+    /// Selectors of the site, the rawkuma ones by default
+    #[serde(default)]
+    #[builder(default)]
+    pub selector_profile: Arc<SelectorProfile>,
+    /// chrono format of the absolute chapter dates when the built-in ones do not match,
+    /// see [`crate::types::ChapterDate::parse_with_format`]
+    #[serde(default)]
+    #[builder(default, setter(into, strip_option))]
+    pub date_format: Option<String>,
+}
+
+impl Default for SourceConfig {
+    fn default() -> Self {
+        Self::rawkuma()
+    }
+}
+
+impl SourceConfig {
+    pub fn rawkuma() -> Self {
+        Self {
+            id: "rawkuma".to_string(),
+            name: "Rawkuma".to_string(),
+            base_url: Url::parse(BASE_URL).expect("Error on parsing the BASE_URL"),
+            language: "ja".to_string(),
+            selector_profile: Default::default(),
+            date_format: None,
+        }
+    }
+    pub fn from_json(json: &str) -> RawKumaResult<Self> {
+        let config: Self = serde_json::from_str(json)?;
+        config.selector_profile.validate()?;
+        RawKumaResult::Ok(config)
+    }
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> RawKumaResult<Self> {
+        let config: Self = toml::from_str(toml)?;
+        config.selector_profile.validate()?;
+        RawKumaResult::Ok(config)
+    }
+    /// Read a config from a `.json` file, or a `.toml` file with the `toml` feature
+    pub fn from_path(path: impl AsRef<Path>) -> RawKumaResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml(&content),
+            _ => Self::from_json(&content),
+        }
+    }
+    /// Context of the parses of the site's pages
+    pub fn parse_context(&self, mode: ParseMode) -> ParseContext {
+        ParseContext::new(mode)
+            .with_selector_profile(self.selector_profile.clone())
+            .with_date_format(self.date_format.clone())
+    }
+}
+
+/// A site that can be browsed with the parsers of this crate.
+///
+/// [`crate::RawKumaClient`] implements it for rawkuma, and for any sibling site
+/// given its [`SourceConfig`].
+#[async_trait]
+pub trait Source: Send + Sync {
+    fn config(&self) -> &SourceConfig;
+    fn id(&self) -> &str {
+        &self.config().id
+    }
+    fn name(&self) -> &str {
+        &self.config().name
+    }
+    fn base_url(&self) -> &Url {
+        &self.config().base_url
+    }
+    fn language(&self) -> &str {
+        &self.config().language
+    }
+    fn selector_profile(&self) -> &SelectorProfile {
+        &self.config().selector_profile
+    }
+    fn date_format(&self) -> Option<&str> {
+        self.config().date_format.as_deref()
+    }
+    async fn home(&self) -> RawKumaResult<RawKumaHomeData>;
+    async fn search(&self, query: &str, page: u32) -> RawKumaResult<RawKumaSearch>;
+    async fn manga_list(&self, params: &MangaListParameter) -> RawKumaResult<RawKumaMangaList>;
+    /// `manga` is a slug or the url of the series page
+    async fn manga_details(&self, manga: &str) -> RawKumaResult<RawKumaMangaDetailData>;
+    /// `chapter` is a slug or the url of the reader page
+    async fn chapter(&self, chapter: &str) -> RawKumaResult<RawKumaChapterData>;
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::future::join_all;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use super::Source;
+use crate::{
+    client::timer,
+    types::{error::Error, BsxTitleData, RawKumaResult},
+};
+
+/// Time a source has to answer a [`SourceRegistry::search`]
+pub const DEFAULT_SOURCE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Lowercase `title`, keeping only its letters and digits separated by single spaces,
+/// so `Kanojo, Okarishimasu!` and `kanojo okarishimasu` match.
+pub fn normalize_title(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Key a search result is grouped by: its [`normalize_title`], or when the title
+/// has no letter or digit, the raw title, or the url for an empty title
+fn get_group_key(title: &BsxTitleData) -> String {
+    let normalized = normalize_title(&title.title);
+    if !normalized.is_empty() {
+        normalized
+    } else if !title.title.trim().is_empty() {
+        title.title.trim().to_string()
+    } else {
+        title.url.to_string()
+    }
+}
+
+/// A search result and the id of the source it was found on
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SourcedTitle {
+    pub source: String,
+    pub title: BsxTitleData,
+}
+
+/// A series found on one or more sources
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct UnifiedTitle {
+    /// Title of the first result
+    pub title: String,
+    /// Key the results are grouped by, see [`normalize_title`], or the raw
+    /// title or url of the first result when its title has no letter or digit
+    pub normalized_title: String,
+    /// The results, in the registration order of their sources
+    pub results: Vec<SourcedTitle>,
+}
+
+/// A source whose search failed or timed out
+#[derive(Debug)]
+pub struct SourceFailure {
+    pub source: String,
+    pub error: Error,
+}
+
+/// Results of [`SourceRegistry::search`]
+#[derive(Default)]
+pub struct UnifiedSearch {
+    /// Series in the order they were first found
+    pub titles: Vec<UnifiedTitle>,
+    /// Sources that failed, their results being missing from `titles`
+    pub failures: Vec<SourceFailure>,
+}
+
+/// The sources shown together, such as one search box over every followed site.
+#[derive(Clone)]
+pub struct SourceRegistry {
+    sources: Vec<Arc<dyn Source>>,
+    timeout: Duration,
+}
+
+impl Default for SourceRegistry {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            timeout: DEFAULT_SOURCE_TIMEOUT,
+        }
+    }
+}
+
+impl SourceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Give up on a source that does not answer a search within `timeout`,
+    /// [`DEFAULT_SOURCE_TIMEOUT`] by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+    /// Add `source`, failing when a source with the same id is registered.
+    pub fn register(&mut self, source: impl Source + 'static) -> RawKumaResult<()> {
+        self.register_arc(Arc::new(source))
+    }
+    pub fn register_arc(&mut self, source: Arc<dyn Source>) -> RawKumaResult<()> {
+        if self.get(source.id()).is_some() {
+            return RawKumaResult::Err(Error::DuplicateSource(source.id().to_string()));
+        }
+        self.sources.push(source);
+        RawKumaResult::Ok(())
+    }
+    pub fn with_source(mut self, source: impl Source + 'static) -> RawKumaResult<Self> {
+        self.register(source)?;
+        RawKumaResult::Ok(self)
+    }
+    /// Remove and return the source `id`
+    pub fn remove(&mut self, id: &str) -> Option<Arc<dyn Source>> {
+        let index = self.sources.iter().position(|source| source.id() == id)?;
+        Some(self.sources.remove(index))
+    }
+    pub fn get(&self, id: &str) -> Option<Arc<dyn Source>> {
+        self.sources
+            .iter()
+            .find(|source| source.id() == id)
+            .cloned()
+    }
+    pub fn sources(&self) -> &[Arc<dyn Source>] {
+        &self.sources
+    }
+    /// Search `page` of `query` on every source concurrently.
+    ///
+    /// The results are grouped by [`normalize_title`], a series listed twice by
+    /// the same source being kept once. A source that fails or does not answer
+    /// within the timeout is reported in [`UnifiedSearch::failures`] without
+    /// failing the search.
+    pub async fn search(&self, query: &str, page: u32) -> UnifiedSearch {
+        let searches = self.sources.iter().map(|source| async move {
+            let id = source.id().to_string();
+            let result = timer::timeout(self.timeout, source.search(query, page))
+                .await
+                .unwrap_or_else(|| Err(Error::SourceTimeout(id.clone(), self.timeout)));
+            (id, result)
+        });
+        let mut unified = UnifiedSearch::default();
+        let mut indexes: HashMap<String, usize> = HashMap::new();
+        let mut seen: HashSet<(String, Url)> = HashSet::new();
+        for (source, result) in join_all(searches).await {
+            let search = match result {
+                Ok(search) => search,
+                Err(error) => {
+                    unified.failures.push(SourceFailure { source, error });
+                    continue;
+                }
+            };
+            for title in search.result {
+                if !seen.insert((source.clone(), title.url.clone())) {
+                    continue;
+                }
+                let normalized_title = get_group_key(&title);
+                let sourced = SourcedTitle {
+                    source: source.clone(),
+                    title,
+                };
+                match indexes.get(&normalized_title) {
+                    Some(index) => unified.titles[*index].results.push(sourced),
+                    None => {
+                        indexes.insert(normalized_title.clone(), unified.titles.len());
+                        unified.titles.push(UnifiedTitle {
+                            title: sourced.title.title.clone(),
+                            normalized_title,
+                            results: vec![sourced],
+                        });
+                    }
+                }
+            }
+        }
+        unified
+    }
+}
@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, CONTENT_TYPE},
+    Client, StatusCode, Url,
+};
+
+use crate::types::{error::Error, RawKumaResult};
+
+/// Characters of the body kept in [`Error::HttpStatus`]
+const BODY_SNIPPET_LENGTH: usize = 200;
+
+/// Bytes at the start of the body searched for the challenge markers
+const CHALLENGE_SCAN_LENGTH: usize = 8 * 1024;
+
+/// Markers of the anti-bot interstitials served instead of the page
+const CHALLENGE_MARKERS: [&str; 6] = [
+    "_cf_chl_opt",
+    "cdn-cgi/challenge-platform",
+    "cf-browser-verification",
+    "<title>Attention Required! | Cloudflare</title>",
+    "<title>DDoS-Guard</title>",
+    "ddos-guard/js-challenge",
+];
+
+mod cassette;
+mod memory;
+
+#[cfg(feature = "cassette")]
+pub use cassette::{Cassette, Interaction, RecordingTransport, ReplayTransport};
+pub use cassette::{RecordedBody, RecordedRequest, RecordedResponse};
+pub use memory::MemoryTransport;
+
+/// A fully read HTTP response.
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    /// Final url of the response, after the redirections
+    pub url: Url,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl TransportResponse {
+    pub fn new(url: Url, status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            url,
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+    /// The body decoded as UTF-8, invalid sequences are replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+    /// Whether the `Content-Type` is `text/html`
+    pub fn is_html(&self) -> bool {
+        self.headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| {
+                value
+                    .trim_start()
+                    .to_ascii_lowercase()
+                    .starts_with("text/html")
+            })
+    }
+    /// Whether the response is a Cloudflare or DDoS-Guard interstitial instead of the page.
+    ///
+    /// Besides the `cf-mitigated` header, only the first kilobytes of `text/html`
+    /// bodies are searched. Successful responses are only checked for the
+    /// interstitial title, since the regular pages also load the challenge scripts.
+    pub fn is_challenge_page(&self) -> bool {
+        if self
+            .headers
+            .get("cf-mitigated")
+            .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"challenge"))
+        {
+            return true;
+        }
+        if !self.is_html() {
+            return false;
+        }
+        let head = &self.body[..self.body.len().min(CHALLENGE_SCAN_LENGTH)];
+        let text = String::from_utf8_lossy(head);
+        if text.contains("<title>Just a moment...</title>") {
+            return true;
+        }
+        !self.status.is_success() && CHALLENGE_MARKERS.iter().any(|marker| text.contains(marker))
+    }
+    /// Start of the body with the whitespace collapsed, at most `length` characters long.
+    pub fn get_body_snippet(&self, length: usize) -> String {
+        self.text()
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .chars()
+            .take(length)
+            .collect()
+    }
+    /// Turn a challenge page into an [`Error::ChallengePage`], a `404` into an
+    /// [`Error::NotFound`] and any other non-2xx response into an [`Error::HttpStatus`].
+    pub fn error_for_status(self) -> RawKumaResult<Self> {
+        if self.is_challenge_page() {
+            RawKumaResult::Err(Error::ChallengePage { url: self.url })
+        } else if self.status.is_success() {
+            RawKumaResult::Ok(self)
+        } else if self.status == StatusCode::NOT_FOUND {
+            RawKumaResult::Err(Error::NotFound { url: self.url })
+        } else {
+            RawKumaResult::Err(Error::HttpStatus {
+                body_snippet: self.get_body_snippet(BODY_SNIPPET_LENGTH),
+                url: self.url,
+                status: self.status.as_u16(),
+            })
+        }
+    }
+}
+
+/// Sends the requests of a [`RawKumaClient`](crate::RawKumaClient).
+///
+/// The default implementation is [`ReqwestTransport`].
+/// [`MemoryTransport`] serves saved pages instead, to run the client offline,
+/// and, with the `cassette` feature, `RecordingTransport` / `ReplayTransport`
+/// capture a session once and play it back.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn get(&self, url: Url, headers: HeaderMap) -> RawKumaResult<TransportResponse>;
+}
+
+/// Send the requests over the network with a [`reqwest::Client`].
+#[derive(Clone, Default)]
+pub struct ReqwestTransport {
+    http_client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(http_client: Client) -> Self {
+        Self { http_client }
+    }
+}
+
+impl From<Client> for ReqwestTransport {
+    fn from(http_client: Client) -> Self {
+        Self::new(http_client)
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn get(&self, url: Url, headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        let req = self.http_client.get(url).headers(headers).build()?;
+        let res = self.http_client.execute(req).await?;
+        let url = res.url().clone();
+        let status = res.status();
+        let headers = res.headers().clone();
+        let body = res.bytes().await?.to_vec();
+        RawKumaResult::Ok(TransportResponse::new(url, status, body).with_headers(headers))
+    }
+}
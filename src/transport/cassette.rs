@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "cassette")]
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+#[cfg(feature = "cassette")]
+use async_trait::async_trait;
+#[cfg(feature = "cassette")]
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{error::Error, RawKumaResult};
+
+#[cfg(feature = "cassette")]
+use super::Transport;
+use super::TransportResponse;
+
+/// A recorded browsing session, saved as a JSON file.
+#[cfg(feature = "cassette")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// A request and the response it received.
+#[cfg(feature = "cassette")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub url: Url,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// Final url of the response, after the redirections
+    pub url: Url,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: RecordedBody,
+}
+
+/// Text bodies are kept as is so the cassette stays readable,
+/// the others (images) are encoded in base64 with the `cassette` feature.
+/// Without it, only the pages are recorded, in the response cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedBody {
+    Text(String),
+    #[cfg(feature = "cassette")]
+    Base64(String),
+}
+
+fn headers_to_map(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn map_to_headers(map: &BTreeMap<String, String>) -> HeaderMap {
+    map.iter()
+        .filter_map(|(name, value)| {
+            Some((
+                HeaderName::from_bytes(name.as_bytes()).ok()?,
+                HeaderValue::from_str(value).ok()?,
+            ))
+        })
+        .collect()
+}
+
+impl RecordedBody {
+    pub fn new(body: &[u8]) -> Self {
+        match std::str::from_utf8(body) {
+            Ok(text) => Self::Text(text.to_string()),
+            #[cfg(feature = "cassette")]
+            Err(_) => Self::Base64(STANDARD.encode(body)),
+            #[cfg(not(feature = "cassette"))]
+            Err(_) => Self::Text(String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+    pub fn to_bytes(&self) -> RawKumaResult<Vec<u8>> {
+        match self {
+            Self::Text(text) => RawKumaResult::Ok(text.as_bytes().to_vec()),
+            #[cfg(feature = "cassette")]
+            Self::Base64(data) => RawKumaResult::Ok(STANDARD.decode(data)?),
+        }
+    }
+}
+
+impl From<&TransportResponse> for RecordedResponse {
+    fn from(response: &TransportResponse) -> Self {
+        Self {
+            url: response.url.clone(),
+            status: response.status.as_u16(),
+            headers: headers_to_map(&response.headers),
+            body: RecordedBody::new(&response.body),
+        }
+    }
+}
+
+impl TryFrom<&RecordedResponse> for TransportResponse {
+    type Error = Error;
+    fn try_from(response: &RecordedResponse) -> RawKumaResult<Self> {
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|_| Error::InvalidStatusCode(response.status))?;
+        RawKumaResult::Ok(
+            TransportResponse::new(response.url.clone(), status, response.body.to_bytes()?)
+                .with_headers(map_to_headers(&response.headers)),
+        )
+    }
+}
+
+#[cfg(feature = "cassette")]
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> RawKumaResult<Self> {
+        let content = std::fs::read(path)?;
+        RawKumaResult::Ok(serde_json::from_slice(&content)?)
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> RawKumaResult<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        RawKumaResult::Ok(())
+    }
+}
+
+/// Record every request sent through `inner` in a [`Cassette`].
+///
+/// The interactions are kept in memory. A transport made with
+/// [`RecordingTransport::to_file`] writes them at its path on
+/// [`RecordingTransport::save`], and when the last clone of the transport is
+/// dropped with interactions recorded since the last save.
+///
+/// ```no_run
+/// use rawkuma_scraper::{
+///     transport::{RecordingTransport, ReqwestTransport},
+///     RawKumaClient,
+/// };
+///
+/// let transport = RecordingTransport::to_file(ReqwestTransport::default(), "session.json");
+/// let client = RawKumaClient::with_transport(transport.clone());
+/// // browse with the client, then
+/// transport.save().unwrap();
+/// ```
+#[cfg(feature = "cassette")]
+#[derive(Clone)]
+pub struct RecordingTransport<T: Transport> {
+    inner: T,
+    recording: Arc<Recording>,
+}
+
+/// The cassette shared by the clones of a [`RecordingTransport`]
+#[cfg(feature = "cassette")]
+struct Recording {
+    cassette: Mutex<Cassette>,
+    path: Option<PathBuf>,
+    /// Number of interactions written by the last save
+    saved: Mutex<usize>,
+}
+
+#[cfg(feature = "cassette")]
+impl Recording {
+    fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            cassette: Default::default(),
+            path,
+            saved: Default::default(),
+        }
+    }
+    fn save(&self) -> RawKumaResult<()> {
+        let Some(path) = &self.path else {
+            return RawKumaResult::Ok(());
+        };
+        let cassette = self
+            .cassette
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        cassette.save(path)?;
+        *self
+            .saved
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = cassette.interactions.len();
+        RawKumaResult::Ok(())
+    }
+    fn is_saved(&self) -> bool {
+        let recorded = self
+            .cassette
+            .lock()
+            .map(|cassette| cassette.interactions.len())
+            .unwrap_or_default();
+        self.path.is_none() || self.saved.lock().is_ok_and(|saved| *saved == recorded)
+    }
+}
+
+#[cfg(feature = "cassette")]
+impl Drop for Recording {
+    fn drop(&mut self) {
+        if self.is_saved() {
+            return;
+        }
+        if let Err(error) = self.save() {
+            log::warn!("The cassette could not be saved: {}", error);
+        }
+    }
+}
+
+#[cfg(feature = "cassette")]
+impl<T: Transport> RecordingTransport<T> {
+    /// Record in memory only, read the interactions with [`RecordingTransport::cassette`].
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            recording: Arc::new(Recording::new(None)),
+        }
+    }
+    /// Record and write the cassette at `path` on [`RecordingTransport::save`] and on drop.
+    pub fn to_file(inner: T, path: impl AsRef<Path>) -> Self {
+        Self {
+            inner,
+            recording: Arc::new(Recording::new(Some(path.as_ref().to_path_buf()))),
+        }
+    }
+    /// Write the interactions recorded so far at the path, if one is set.
+    pub fn save(&self) -> RawKumaResult<()> {
+        self.recording.save()
+    }
+    /// The interactions recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.recording
+            .cassette
+            .lock()
+            .map(|cassette| cassette.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "cassette")]
+#[async_trait]
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn get(&self, url: Url, headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        let request = RecordedRequest {
+            url: url.clone(),
+            headers: headers_to_map(&headers),
+        };
+        let response = self.inner.get(url, headers).await?;
+        if let Ok(mut cassette) = self.recording.cassette.lock() {
+            cassette.interactions.push(Interaction {
+                request,
+                response: RecordedResponse::from(&response),
+            });
+        }
+        RawKumaResult::Ok(response)
+    }
+}
+
+/// Serve the responses of a [`Cassette`] without any network access.
+///
+/// Requests are matched by url. When an url was recorded several times,
+/// its responses are served in the recorded order and the last one is repeated.
+/// An url missing from the cassette is an [`Error::NotRecorded`].
+#[cfg(feature = "cassette")]
+#[derive(Clone)]
+pub struct ReplayTransport {
+    responses: Arc<HashMap<Url, Vec<TransportResponse>>>,
+    cursors: Arc<Mutex<HashMap<Url, usize>>>,
+}
+
+#[cfg(feature = "cassette")]
+impl ReplayTransport {
+    pub fn new(cassette: &Cassette) -> RawKumaResult<Self> {
+        let mut responses: HashMap<Url, Vec<TransportResponse>> = HashMap::new();
+        for interaction in &cassette.interactions {
+            responses
+                .entry(interaction.request.url.clone())
+                .or_default()
+                .push(TransportResponse::try_from(&interaction.response)?);
+        }
+        RawKumaResult::Ok(Self {
+            responses: Arc::new(responses),
+            cursors: Default::default(),
+        })
+    }
+    pub fn load(path: impl AsRef<Path>) -> RawKumaResult<Self> {
+        Self::new(&Cassette::load(path)?)
+    }
+}
+
+#[cfg(feature = "cassette")]
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn get(&self, url: Url, _headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        let responses = self
+            .responses
+            .get(&url)
+            .ok_or(Error::NotRecorded(url.clone()))?;
+        let index = match self.cursors.lock() {
+            Ok(mut cursors) => {
+                let cursor = cursors.entry(url.clone()).or_default();
+                let index = (*cursor).min(responses.len() - 1);
+                *cursor += 1;
+                index
+            }
+            Err(_) => 0,
+        };
+        RawKumaResult::Ok(responses[index].clone())
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use reqwest::{header::HeaderMap, StatusCode, Url};
+
+use crate::types::RawKumaResult;
+
+use super::{Transport, TransportResponse};
+
+/// Serve responses from memory, without any network access.
+///
+/// Responses are looked up by exact url in a map, then in a directory of
+/// fixtures when one is set. Unknown urls get an empty `404 Not Found`.
+///
+/// ```
+/// use rawkuma_scraper::{transport::MemoryTransport, RawKumaClient, Url};
+///
+/// let transport = MemoryTransport::new().with_html(
+///     Url::parse("https://rawkuma.com/").unwrap(),
+///     "<html><body><div id=\"content\"></div></body></html>",
+/// );
+/// let client = RawKumaClient::with_transport(transport);
+/// ```
+#[derive(Clone, Default)]
+pub struct MemoryTransport {
+    responses: Arc<RwLock<HashMap<Url, TransportResponse>>>,
+    directory: Option<PathBuf>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Serve the files of `directory`, see [`MemoryTransport::get_fixture_path`] for the layout.
+    pub fn from_directory(directory: impl AsRef<Path>) -> Self {
+        Self {
+            responses: Default::default(),
+            directory: Some(directory.as_ref().to_path_buf()),
+        }
+    }
+    /// Path of the file served for `url`, relative to the fixtures directory.
+    ///
+    /// It is made of the host followed by the url path, with `index.html` for paths
+    /// ending with `/`. The query, if any, is appended to the file stem after a `@`,
+    /// with the characters other than ASCII alphanumerics, `-`, `_`, `.`, `=` and `&`
+    /// replaced by `_`.
+    ///
+    /// `https://rawkuma.com/page/2/?s=kanojo` is served from `rawkuma.com/page/2/index@s=kanojo.html`.
+    pub fn get_fixture_path(url: &Url) -> PathBuf {
+        let mut path = PathBuf::from(url.host_str().unwrap_or_default());
+        let mut segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.collect())
+            .unwrap_or_default();
+        let file_name = match segments.pop() {
+            None | Some("") => "index.html",
+            Some(name) => name,
+        };
+        for segment in segments.into_iter().filter(|segment| !segment.is_empty()) {
+            path.push(segment);
+        }
+        match url.query() {
+            None => path.push(file_name),
+            Some(query) => {
+                let query: String = query
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || "-_.=&".contains(c) {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect();
+                let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+                path.push(if extension.is_empty() {
+                    format!("{}@{}", stem, query)
+                } else {
+                    format!("{}@{}.{}", stem, query, extension)
+                });
+            }
+        }
+        path
+    }
+    /// Serve `response` for `url`, replacing any previous response.
+    pub fn insert(&self, url: Url, response: TransportResponse) {
+        if let Ok(mut responses) = self.responses.write() {
+            responses.insert(url, response);
+        }
+    }
+    /// Serve `body` with a `200 OK` for `url`.
+    pub fn with_html(self, url: Url, body: impl Into<Vec<u8>>) -> Self {
+        self.insert(
+            url.clone(),
+            TransportResponse::new(url, StatusCode::OK, body),
+        );
+        self
+    }
+    /// Serve `response` for `url`.
+    pub fn with_response(self, url: Url, response: TransportResponse) -> Self {
+        self.insert(url, response);
+        self
+    }
+    /// Serve the content of the file at `path` with a `200 OK` for `url`.
+    pub fn with_file(self, url: Url, path: impl AsRef<Path>) -> RawKumaResult<Self> {
+        let body = std::fs::read(path)?;
+        RawKumaResult::Ok(self.with_html(url, body))
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn get(&self, url: Url, _headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        let response = self
+            .responses
+            .read()
+            .ok()
+            .and_then(|responses| responses.get(&url).cloned());
+        if let Some(response) = response {
+            return RawKumaResult::Ok(response);
+        }
+        if let Some(directory) = &self.directory {
+            let path = directory.join(Self::get_fixture_path(&url));
+            if path.is_file() {
+                let body = std::fs::read(path)?;
+                return RawKumaResult::Ok(TransportResponse::new(url, StatusCode::OK, body));
+            }
+        }
+        RawKumaResult::Ok(TransportResponse::new(
+            url,
+            StatusCode::NOT_FOUND,
+            Vec::new(),
+        ))
+    }
+}
@@ -1,35 +1,69 @@
 pub mod bixbox;
 pub mod bsx;
 pub mod chapter;
+pub mod chapter_date;
+pub mod chapter_number;
 pub mod chapterlist;
 pub mod error;
 pub mod genre_tag;
 pub mod home;
 pub mod manga;
+pub mod pagination;
+pub mod parse_mode;
 pub mod search;
+pub mod slug;
 pub mod utao;
 
 pub use bixbox::{BixboxData, BixboxDataBuilder};
 pub use bsx::{BsxTitleData, BsxTitleDataBuilder};
+pub use chapter_date::{ChapterDate, TimeUnit};
+pub use chapter_number::{ChapterKind, ChapterNumber};
 pub use chapterlist::{Chapter, ChapterBuilder, ChapterList, ChapterListBuilder};
 pub use error::RawKumaResult;
 pub use genre_tag::{MgenTag, MgenTagBuilder};
+pub use pagination::{Pagination, PaginationBuilder};
+pub use parse_mode::{ParseContext, ParseMode, ParseWarning, ParseWarningHandler, Parsed};
 use scraper::ElementRef;
+pub use slug::{ChapterSlug, MangaSlug};
 pub use utao::{UtaoTitleChapter, UtaoTitleChapterBuilder, UtaoTitleData, UtaoTitleDataBuilder};
 
-use crate::parser::HtmlParser;
+use crate::parser::{HtmlParser, SelectorProfile};
 
 pub trait FromElementRef<'a> {
+    /// Selector of the element in `profile`, reported by the [`ParseWarning`]s
+    fn selector(profile: &SelectorProfile) -> String;
+    /// Parse the element with the default [`ParseContext`]
     fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_element_ref_with(data, &ParseContext::default())
+    }
+    /// Parse the element with the selectors of `context`,
+    /// the lists nested in it following its mode
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self>
     where
         Self: Sized;
-    fn from_vec_element(elements: &'a Vec<ElementRef<'a>>) -> RawKumaResult<Vec<Self>>
+    /// Parse every element, the failed ones being skipped with a warning
+    /// or failing the whole list depending on the mode of `context`
+    fn from_vec_element(
+        elements: &'a Vec<ElementRef<'a>>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Vec<Self>>
     where
         Self: Sized,
     {
         let mut datas: Vec<Self> = Vec::new();
         for element in elements {
-            if let Ok(d) = Self::from_element_ref(element) {
+            let result = Self::from_element_ref_with(element, context);
+            if let Some(d) = context.tolerate(
+                &Self::selector(context.get_selector_profile()),
+                element,
+                result,
+            )? {
                 datas.push(d);
             }
         }
@@ -8,8 +8,10 @@ use serde::{Deserialize, Serialize};
 use getset::Getters;
 
 use crate::constant::BASE_URL;
+use crate::enums::manga::{Status, Type};
+use crate::parser::{profile::parse_selector, SelectorProfile};
 
-use super::{FromElementRef, MgenTag, RawKumaResult};
+use super::{FromElementRef, MgenTag, ParseContext, RawKumaResult};
 
 use chrono::{DateTime, FixedOffset};
 
@@ -26,7 +28,36 @@ pub struct BixboxData {
     pub rating_count: u32,
     pub rating_value: f32,
     pub description: Option<String>,
+    /// The uploader of the series, the `Posted By` row
     pub author: String,
+    /// The `Author` row, the writer of the series
+    #[serde(default)]
+    #[builder(default)]
+    pub writer: Option<String>,
+    #[serde(default)]
+    #[builder(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    #[builder(default)]
+    pub alternative_titles: Vec<String>,
+    #[serde(default)]
+    #[builder(default)]
+    pub status: Option<Status>,
+    #[serde(default, rename = "type")]
+    #[builder(default)]
+    pub type_: Option<Type>,
+    #[serde(default)]
+    #[builder(default)]
+    pub serialization: Option<String>,
+    #[serde(default)]
+    #[builder(default)]
+    pub released: Option<i32>,
+    #[serde(default)]
+    #[builder(default)]
+    pub followers: Option<u64>,
+    #[serde(default)]
+    #[builder(default)]
+    pub views: Option<u64>,
     pub date_published: DateTime<FixedOffset>,
     pub date_modified: DateTime<FixedOffset>,
     pub title: String,
@@ -44,6 +75,15 @@ impl Default for BixboxData {
             rating_value: Default::default(),
             description: Default::default(),
             author: Default::default(),
+            writer: Default::default(),
+            artist: Default::default(),
+            alternative_titles: Default::default(),
+            status: Default::default(),
+            type_: Default::default(),
+            serialization: Default::default(),
+            released: Default::default(),
+            followers: Default::default(),
+            views: Default::default(),
             date_published: Default::default(),
             date_modified: Default::default(),
             title: Default::default(),
@@ -53,233 +93,295 @@ impl Default for BixboxData {
 }
 
 impl BixboxData {
-    pub fn get_bix_box_anime_full_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse("div.bixbox.animefull")?)
+    pub fn get_bix_box_anime_full_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.bixbox)
     }
-    pub fn get_image_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"img[itemprop="image"]"#)?)
+    pub fn get_image_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.image)
     }
-    pub fn get_name_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"h1[itemprop="name"]"#)?)
+    pub fn get_name_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.name)
     }
-    pub fn get_worst_rating_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"meta[itemprop="worstRating"]"#)?)
+    pub fn get_worst_rating_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.worst_rating)
     }
-    pub fn get_best_rating_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"meta[itemprop="bestRating"]"#)?)
+    pub fn get_best_rating_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.best_rating)
     }
-    pub fn get_rating_count_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"meta[itemprop="ratingCount"]"#)?)
+    pub fn get_rating_count_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.rating_count)
     }
-    pub fn get_rating_value_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[itemprop="ratingValue"]"#)?)
+    pub fn get_rating_value_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.rating_value)
     }
-    pub fn get_author_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"span[itemprop="author"]"#)?)
+    pub fn get_author_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.author)
     }
-    pub fn get_author_i_selector_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"i[itemprop="name"]"#)?)
+    pub fn get_author_i_selector_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.author_name)
     }
-    pub fn get_description_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[itemprop="description"]"#)?)
+    pub fn get_description_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.description)
     }
-    pub fn get_date_modified_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"time[itemprop="dateModified"]"#)?)
+    pub fn get_date_modified_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.date_modified)
     }
-    pub fn get_date_published_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"time[itemprop="datePublished"]"#)?)
+    pub fn get_date_published_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.date_published)
+    }
+    /// Rows of the info table, a `<b>` label followed by a `<span>` value
+    pub fn get_info_row_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.info_row)
+    }
+    pub fn get_imptdt_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.imptdt)
+    }
+    pub fn get_followers_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.followers)
+    }
+    fn get_b_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.info_row_label)
+    }
+    fn get_span_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.info_row_value)
+    }
+    fn get_imptdt_value_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.details.imptdt_value)
     }
 
-    pub fn get_image_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_image_selector()?;
+    pub fn get_image_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_image_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"img[itemprop="image"]"#.to_string(),
+                profile.details.image.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
-    pub fn get_name_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_name_selector()?;
+    pub fn get_name_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_name_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"h1[itemprop="name"]"#.to_string(),
+                profile.details.name.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
-    pub fn get_worst_rating_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_worst_rating_selector()?;
+    pub fn get_worst_rating_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_worst_rating_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"meta[itemprop="worstRating"]"#.to_string(),
+                profile.details.worst_rating.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
-    pub fn get_best_rating_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_best_rating_selector()?;
+    pub fn get_best_rating_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_best_rating_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"meta[itemprop="bestRating"]"#.to_string(),
+                profile.details.best_rating.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
-    pub fn get_rating_count_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_rating_count_selector()?;
+    pub fn get_rating_count_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_rating_count_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"meta[itemprop="ratingCount"]"#.to_string(),
+                profile.details.rating_count.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
-    pub fn get_rating_value_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_rating_value_selector()?;
+    pub fn get_rating_value_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_rating_value_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"div[itemprop="ratingValue"]"#.to_string(),
+                profile.details.rating_value.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
-    pub fn get_author_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_author_selector()?;
-        let i_selector = Self::get_author_i_selector_selector()?;
+    pub fn get_author_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_author_selector(profile)?;
+        let i_selector = Self::get_author_i_selector_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"span[itemprop="author"]"#.to_string(),
+                profile.details.author.clone(),
             )),
             Some(d) => match d.select(&i_selector).next() {
                 None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                    r#"i[itemprop="name"]"#.to_string(),
+                    profile.details.author_name.clone(),
                 )),
                 Some(d) => RawKumaResult::Ok(d),
             },
         }
     }
-    pub fn get_description_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_description_selector()?;
+    pub fn get_description_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_description_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"div[itemprop="description"]"#.to_string(),
+                profile.details.description.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
     pub fn get_date_modified_element<'a>(
         data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_date_modified_selector()?;
+        let selector = Self::get_date_modified_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"time[itemprop="dateModified"]"#.to_string(),
+                profile.details.date_modified.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
     pub fn get_date_published_element<'a>(
         data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_date_published_selector()?;
+        let selector = Self::get_date_published_selector(profile)?;
         match data.select(&selector).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"time[itemprop="datePublished"]"#.to_string(),
+                profile.details.date_published.clone(),
             )),
             Some(d) => RawKumaResult::Ok(d),
         }
     }
 
-    pub fn get_image_element_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<(Url, String)> {
-        let element = Self::get_image_element(data)?;
-        let url = Url::parse(
-            format!(
-                "https:{}",
-                match element.value().attr("src") {
-                    None => {
-                        return RawKumaResult::Err(super::error::Error::AttributeNotFound {
-                            name: "src".to_string(),
-                            element: r#"img[itemprop="image"]"#.to_string(),
-                        });
-                    }
-                    Some(d) => d,
-                }
-            )
-            .as_str(),
-        )?;
+    /// The cover url, resolved against the url of the page, and its title
+    pub fn get_image_element_data<'a>(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<(Url, String)> {
+        let profile = context.get_selector_profile();
+        let element = Self::get_image_element(data, profile)?;
+        let url = context.resolve_url(match element.value().attr("src") {
+            None => {
+                return RawKumaResult::Err(super::error::Error::AttributeNotFound {
+                    name: "src".to_string(),
+                    element: profile.details.image.clone(),
+                });
+            }
+            Some(d) => d,
+        })?;
         let title: String = match element.value().attr("title") {
             None => {
                 return RawKumaResult::Err(super::error::Error::AttributeNotFound {
                     name: "title".to_string(),
-                    element: r#"img[itemprop="image"]"#.to_string(),
+                    element: profile.details.image.clone(),
                 });
             }
             Some(d) => d.to_string(),
         };
         RawKumaResult::Ok((url, title))
     }
-    pub fn get_name_element_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<String> {
-        let element = Self::get_name_element(data)?;
+    pub fn get_name_element_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<String> {
+        let element = Self::get_name_element(data, profile)?;
         match element.text().next() {
             None => RawKumaResult::Err(super::error::Error::TextContentFound),
             Some(d) => RawKumaResult::Ok(d.to_string()),
         }
     }
-    pub fn get_worst_rating_element_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<u16> {
-        let element = Self::get_worst_rating_element(data)?;
+    pub fn get_worst_rating_element_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<u16> {
+        let element = Self::get_worst_rating_element(data, profile)?;
         let content = match element.value().attr("content") {
             None => {
                 return RawKumaResult::Err(super::error::Error::AttributeNotFound {
                     name: "content".to_string(),
-                    element: r#"meta[itemprop="worstRating"]"#.to_string(),
+                    element: profile.details.worst_rating.clone(),
                 })
             }
             Some(d) => d.parse::<u16>()?,
         };
         RawKumaResult::Ok(content)
     }
-    pub fn get_best_rating_element_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<u16> {
-        let element = Self::get_best_rating_element(data)?;
+    pub fn get_best_rating_element_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<u16> {
+        let element = Self::get_best_rating_element(data, profile)?;
         let content = match element.value().attr("content") {
             None => {
                 return RawKumaResult::Err(super::error::Error::AttributeNotFound {
                     name: "content".to_string(),
-                    element: r#"meta[itemprop="bestRating"]"#.to_string(),
+                    element: profile.details.best_rating.clone(),
                 })
             }
             Some(d) => d.parse::<u16>()?,
         };
         RawKumaResult::Ok(content)
     }
-    pub fn get_rating_count_element_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<u32> {
-        let element = Self::get_rating_count_element(data)?;
+    pub fn get_rating_count_element_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<u32> {
+        let element = Self::get_rating_count_element(data, profile)?;
         let content = match element.value().attr("content") {
             None => {
                 return RawKumaResult::Err(super::error::Error::AttributeNotFound {
                     name: "content".to_string(),
-                    element: r#"meta[itemprop="ratingCount"]"#.to_string(),
+                    element: profile.details.rating_count.clone(),
                 })
             }
             Some(d) => d.parse::<u32>()?,
         };
         RawKumaResult::Ok(content)
     }
-    pub fn get_rating_value_element_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<f32> {
-        let element = Self::get_rating_value_element(data)?;
+    pub fn get_rating_value_element_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<f32> {
+        let element = Self::get_rating_value_element(data, profile)?;
         let content = match element.value().attr("content") {
             None => {
                 return RawKumaResult::Err(super::error::Error::AttributeNotFound {
                     name: "content".to_string(),
-                    element: r#"div[itemprop="ratingValue"]"#.to_string(),
+                    element: profile.details.rating_value.clone(),
                 })
             }
             Some(d) => d.parse::<f32>()?,
         };
         RawKumaResult::Ok(content)
     }
-    pub fn get_author_element_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<String> {
-        let element = Self::get_author_element(data)?;
+    pub fn get_author_element_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<String> {
+        let element = Self::get_author_element(data, profile)?;
         match element.text().next() {
             None => RawKumaResult::Err(super::error::Error::TextContentFound),
             Some(d) => RawKumaResult::Ok(d.to_string()),
@@ -287,8 +389,9 @@ impl BixboxData {
     }
     pub fn get_description_element_data<'a>(
         data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<Option<String>> {
-        let element = Self::get_description_element(data)?;
+        let element = Self::get_description_element(data, profile)?;
         match element.text().next() {
             None => RawKumaResult::Ok(None),
             Some(d) => RawKumaResult::Ok(Some(d.to_string())),
@@ -296,13 +399,14 @@ impl BixboxData {
     }
     pub fn get_date_modified_element_data<'a>(
         data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<DateTime<FixedOffset>> {
-        let element = Self::get_date_modified_element(data)?;
+        let element = Self::get_date_modified_element(data, profile)?;
         let datetime = match element.value().attr("datetime") {
             None => {
                 return RawKumaResult::Err(super::error::Error::AttributeNotFound {
                     name: "datetime".to_string(),
-                    element: r#"time[itemprop="dateModified"]"#.to_string(),
+                    element: profile.details.date_modified.clone(),
                 })
             }
             Some(d) => DateTime::parse_from_rfc3339(d)?,
@@ -311,41 +415,219 @@ impl BixboxData {
     }
     pub fn get_date_published_element_data<'a>(
         data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<DateTime<FixedOffset>> {
-        let element = Self::get_date_published_element(data)?;
+        let element = Self::get_date_published_element(data, profile)?;
         let datetime = match element.value().attr("datetime") {
             None => {
                 return RawKumaResult::Err(super::error::Error::AttributeNotFound {
                     name: "datetime".to_string(),
-                    element: r#"time[itemprop="datePublished"]"#.to_string(),
+                    element: profile.details.date_published.clone(),
                 })
             }
             Some(d) => DateTime::parse_from_rfc3339(d)?,
         };
         RawKumaResult::Ok(datetime)
     }
+
+    /// Collapse the whitespace of a text, `None` when empty or a `-` placeholder
+    fn clean_text<'b>(text: impl Iterator<Item = &'b str>) -> Option<String> {
+        let text = text
+            .flat_map(str::split_whitespace)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(text).filter(|text| !text.is_empty() && text != "-")
+    }
+    /// Digits of a text such as `Followed by 1,234 people` or `98,765`
+    fn parse_count(text: &str) -> Option<u64> {
+        let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+        digits.parse::<u64>().ok()
+    }
+    /// Value of the info table row labelled `label`, such as `Artist` or `Released`
+    pub fn get_info_row_data<'a>(
+        data: &'a ElementRef<'a>,
+        label: &str,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Option<String>> {
+        let selector = Self::get_info_row_selector(profile)?;
+        let b_selector = Self::get_b_selector(profile)?;
+        let span_selector = Self::get_span_selector(profile)?;
+        for row in data.select(&selector) {
+            let Some(b) = row.select(&b_selector).next() else {
+                continue;
+            };
+            if Self::clean_text(b.text()).as_deref() != Some(label) {
+                continue;
+            }
+            return RawKumaResult::Ok(
+                row.select(&span_selector)
+                    .next()
+                    .and_then(|span| Self::clean_text(span.text())),
+            );
+        }
+        RawKumaResult::Ok(None)
+    }
+    /// Value of the side info block entry labelled `label`, such as `Status` or `Type`
+    pub fn get_imptdt_data<'a>(
+        data: &'a ElementRef<'a>,
+        label: &str,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Option<String>> {
+        let selector = Self::get_imptdt_selector(profile)?;
+        let value_selector = Self::get_imptdt_value_selector(profile)?;
+        for imptdt in data.select(&selector) {
+            let Some(value) = imptdt.select(&value_selector).next() else {
+                continue;
+            };
+            if imptdt.text().next().map(str::trim) != Some(label) {
+                continue;
+            }
+            return RawKumaResult::Ok(Self::clean_text(value.text()));
+        }
+        RawKumaResult::Ok(None)
+    }
+    /// Split the `Alternative Titles` row on `,`, `;` and `|`.
+    ///
+    /// The commas of a title are ambiguous with the separators, so the pieces
+    /// making up `name`, such as `Kanojo, Okarishimasu`, are joined back.
+    pub fn split_alternative_titles(titles: &str, name: &str) -> Vec<String> {
+        let pieces: Vec<&str> = titles
+            .split([',', ';', '|'])
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .collect();
+        let name = Self::clean_text(std::iter::once(name)).unwrap_or_default();
+        let name_pieces = name.split(',').count();
+        let mut titles: Vec<String> = Vec::new();
+        let mut index = 0;
+        while index < pieces.len() {
+            let end = (index + name_pieces).min(pieces.len());
+            let joined = pieces[index..end].join(", ");
+            if name_pieces > 1 && joined == name {
+                titles.push(joined);
+                index = end;
+            } else {
+                titles.push(pieces[index].to_string());
+                index += 1;
+            }
+        }
+        titles
+    }
+    pub fn get_alternative_titles_data<'a>(
+        data: &'a ElementRef<'a>,
+        name: &str,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<String>> {
+        RawKumaResult::Ok(
+            Self::get_info_row_data(data, &profile.labels.alternative_titles, profile)?
+                .map(|titles| Self::split_alternative_titles(&titles, name))
+                .unwrap_or_default(),
+        )
+    }
+    /// The writer of the series, or the uploader when the page does not name one
+    pub fn get_credited_author(&self) -> &str {
+        self.writer.as_deref().unwrap_or(self.author.as_str())
+    }
+    pub fn get_status_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Option<Status>> {
+        RawKumaResult::Ok(
+            Self::get_imptdt_data(data, &profile.labels.status, profile)?
+                .map(|status| Status::from(status.to_lowercase().as_str()))
+                .filter(|status| !matches!(status, Status::All)),
+        )
+    }
+    pub fn get_type_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Option<Type>> {
+        RawKumaResult::Ok(
+            Self::get_imptdt_data(data, &profile.labels.type_, profile)?
+                .map(|type_| Type::from(type_.to_lowercase().as_str()))
+                .filter(|type_| !matches!(type_, Type::All)),
+        )
+    }
+    pub fn get_released_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Option<i32>> {
+        RawKumaResult::Ok(
+            Self::get_info_row_data(data, &profile.labels.released, profile)?
+                .and_then(|year| year.parse::<i32>().ok()),
+        )
+    }
+    pub fn get_views_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Option<u64>> {
+        RawKumaResult::Ok(
+            Self::get_info_row_data(data, &profile.labels.views, profile)?
+                .and_then(|views| Self::parse_count(&views)),
+        )
+    }
+    pub fn get_followers_data<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Option<u64>> {
+        let selector = Self::get_followers_selector(profile)?;
+        RawKumaResult::Ok(
+            data.select(&selector)
+                .next()
+                .and_then(|bmc| Self::parse_count(&bmc.text().collect::<String>())),
+        )
+    }
 }
 
 impl<'a> FromElementRef<'a> for BixboxData {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self>
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.details.bixbox.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
-        let (image, title) = Self::get_image_element_data(data)?;
+        let profile = context.get_selector_profile();
+        let (image, title) = Self::get_image_element_data(data, context)?;
+        let name = Self::get_name_element_data(data, profile)?;
         RawKumaResult::Ok(
             BixboxDataBuilder::default()
                 .image(image)
                 .title(title)
-                .name(Self::get_name_element_data(data)?)
-                .worst_rating(Self::get_worst_rating_element_data(data)?)
-                .best_rating(Self::get_best_rating_element_data(data)?)
-                .rating_count(Self::get_rating_count_element_data(data)?)
-                .rating_value(Self::get_rating_value_element_data(data)?)
-                .description(Self::get_description_element_data(data)?)
-                .author(Self::get_author_element_data(data)?)
-                .date_modified(Self::get_date_modified_element_data(data)?)
-                .date_published(Self::get_date_published_element_data(data)?)
-                .genres(MgenTag::get_tags_elements_data(data)?)
+                .alternative_titles(Self::get_alternative_titles_data(data, &name, profile)?)
+                .name(name)
+                .worst_rating(Self::get_worst_rating_element_data(data, profile)?)
+                .best_rating(Self::get_best_rating_element_data(data, profile)?)
+                .rating_count(Self::get_rating_count_element_data(data, profile)?)
+                .rating_value(Self::get_rating_value_element_data(data, profile)?)
+                .description(Self::get_description_element_data(data, profile)?)
+                .author(Self::get_author_element_data(data, profile)?)
+                .writer(Self::get_info_row_data(
+                    data,
+                    &profile.labels.author,
+                    profile,
+                )?)
+                .artist(Self::get_info_row_data(
+                    data,
+                    &profile.labels.artist,
+                    profile,
+                )?)
+                .status(Self::get_status_data(data, profile)?)
+                .type_(Self::get_type_data(data, profile)?)
+                .serialization(Self::get_info_row_data(
+                    data,
+                    &profile.labels.serialization,
+                    profile,
+                )?)
+                .released(Self::get_released_data(data, profile)?)
+                .followers(Self::get_followers_data(data, profile)?)
+                .views(Self::get_views_data(data, profile)?)
+                .date_modified(Self::get_date_modified_element_data(data, profile)?)
+                .date_published(Self::get_date_published_element_data(data, profile)?)
+                .genres(MgenTag::get_tags_elements_data(data, context)?)
                 .build()?,
         )
     }
@@ -7,9 +7,12 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "getset")]
 use getset::Getters;
 
+use crate::parser::{profile::parse_selector, SelectorProfile};
 use crate::{client::RawKumaClientFromUrl, RawKumaClient};
 
-use super::{manga::RawKumaMangaDetailData, FromElementRef, RawKumaResult};
+use super::{
+    manga::RawKumaMangaDetailData, FromElementRef, MangaSlug, ParseContext, RawKumaResult,
+};
 
 #[derive(Builder, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "getset", derive(Getters))]
@@ -31,12 +34,16 @@ pub struct TitleData {
 }
 
 impl BsxTitleData {
-    pub fn div_bsx_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[class="bsx"]"#)?)
+    /// Slug of the series, read from its url
+    pub fn slug(&self) -> RawKumaResult<MangaSlug> {
+        MangaSlug::from_url(&self.url)
+    }
+    pub fn div_bsx_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.bsx.card)
     }
     pub async fn get_url_manga_detail(
         &self,
-        client: &mut RawKumaClient,
+        client: &RawKumaClient,
     ) -> RawKumaResult<RawKumaMangaDetailData> {
         RawKumaClientFromUrl::manga_details(client, self.url.clone()).await
     }
@@ -44,70 +51,89 @@ impl BsxTitleData {
         let req = client.get(self.image.clone()).build()?;
         RawKumaResult::Ok(client.execute(req).await?)
     }
-    pub fn get_rating_selector() -> RawKumaResult<Selector> {
-        Ok(Selector::parse(r#"div[class="numscore"]"#)?)
+    pub fn get_rating_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.bsx.rating)
     }
-    pub fn get_bsx_elements<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<Vec<ElementRef<'a>>> {
-        let selector = Self::div_bsx_selector()?;
+    pub fn get_bsx_elements<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<ElementRef<'a>>> {
+        let selector = Self::div_bsx_selector(profile)?;
         RawKumaResult::Ok(data.select(&selector).collect())
     }
-    pub fn get_title_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        match data.select(&(Selector::parse(r#"a"#)?)).next() {
-            None => RawKumaResult::Err(super::error::Error::ElementNotFound("a".to_string())),
+    pub fn get_title_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        match data.select(&(parse_selector(&profile.bsx.link)?)).next() {
+            None => RawKumaResult::Err(super::error::Error::ElementNotFound(
+                profile.bsx.link.clone(),
+            )),
             Some(d) => Ok(d),
         }
     }
-    pub fn get_image_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        match data.select(&(Selector::parse("img")?)).next() {
-            None => Err(super::error::Error::ElementNotFound("img".to_string())),
+    pub fn get_image_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        match data.select(&(parse_selector(&profile.bsx.image)?)).next() {
+            None => Err(super::error::Error::ElementNotFound(
+                profile.bsx.image.clone(),
+            )),
             Some(d) => Ok(d),
         }
     }
-    pub fn get_rating_element<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        match data.select(&(Self::get_rating_selector()?)).next() {
+    pub fn get_rating_element<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        match data.select(&(Self::get_rating_selector(profile)?)).next() {
             None => RawKumaResult::Err(super::error::Error::ElementNotFound(
-                r#"div[class="numscore"]"#.to_string(),
+                profile.bsx.rating.clone(),
             )),
             Some(d) => Ok(d),
         }
     }
-    pub fn get_title_data<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<TitleData> {
-        let _title = Self::get_title_element(data)?;
+    /// The title and the url of the series, resolved against the url of the page
+    pub fn get_title_data<'a>(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<TitleData> {
+        let profile = context.get_selector_profile();
+        let _title = Self::get_title_element(data, profile)?;
         Ok(TitleData {
             title: _title
                 .value()
                 .attr("title")
                 .ok_or(super::error::Error::AttributeNotFound {
                     name: "title".to_string(),
-                    element: "a".to_string(),
+                    element: profile.bsx.link.clone(),
                 })?
                 .to_string(),
-            url: Url::parse(_title.value().attr("href").ok_or(
+            url: context.resolve_url(_title.value().attr("href").ok_or(
                 super::error::Error::AttributeNotFound {
                     name: "href".to_string(),
-                    element: "a".to_string(),
+                    element: profile.bsx.link.clone(),
                 },
             )?)?,
         })
     }
-    pub fn get_img_url<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<Url> {
-        let image = Self::get_image_element(data)?;
-        Ok(Url::parse(
-            format!(
-                "https:{}",
-                image
-                    .value()
-                    .attr("src")
-                    .ok_or(super::error::Error::AttributeNotFound {
-                        name: "href".to_string(),
-                        element: "a".to_string(),
-                    })?
-            )
-            .as_str(),
+    /// The cover url, resolved against the url of the page
+    pub fn get_img_url<'a>(data: &'a ElementRef<'a>, context: &ParseContext) -> RawKumaResult<Url> {
+        let profile = context.get_selector_profile();
+        let image = Self::get_image_element(data, profile)?;
+        context.resolve_url(image.value().attr("src").ok_or(
+            super::error::Error::AttributeNotFound {
+                name: "src".to_string(),
+                element: profile.bsx.image.clone(),
+            },
         )?)
     }
-    pub fn get_rating<'a>(data: &'a ElementRef<'a>) -> RawKumaResult<f64> {
-        let rating = Self::get_rating_element(data)?;
+    pub fn get_rating<'a>(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<f64> {
+        let rating = Self::get_rating_element(data, profile)?;
         Ok(rating
             .text()
             .next()
@@ -117,14 +143,21 @@ impl BsxTitleData {
 }
 
 impl<'a> FromElementRef<'a> for BsxTitleData {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self> {
-        let title = Self::get_title_data(data)?;
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.bsx.card.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self> {
+        let profile = context.get_selector_profile();
+        let title = Self::get_title_data(data, context)?;
 
         RawKumaResult::Ok(
             BsxTitleDataBuilder::default()
                 .title(title.title)
-                .rating(Self::get_rating(data)?)
-                .image(Self::get_img_url(data)?)
+                .rating(Self::get_rating(data, profile)?)
+                .image(Self::get_img_url(data, context)?)
                 .url(title.url)
                 .build()?,
         )
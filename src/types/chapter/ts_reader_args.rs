@@ -2,7 +2,8 @@ use scraper::{ElementRef, Selector};
 use serde::{Deserialize, Deserializer, Serialize};
 use url::Url;
 
-use crate::types::{error::Error, FromElementRef, RawKumaResult};
+use crate::parser::{profile::parse_selector, SelectorProfile};
+use crate::types::{error::Error, ChapterSlug, FromElementRef, ParseContext, RawKumaResult};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "getset", derive(Getters))]
@@ -81,24 +82,43 @@ fn extract_json_from_string<T: for<'a> Deserialize<'a>>(input: &str) -> RawKumaR
 }
 
 impl TSReaderArgs {
-    pub fn get_ts_reader_script_selector() -> RawKumaResult<Selector> {
-        Ok(Selector::parse("div.readingnav.rnavbot + script")?)
+    /// Slug of the previous chapter, `None` on the first one
+    pub fn prev_slug(&self) -> RawKumaResult<Option<ChapterSlug>> {
+        self.prev_url
+            .as_ref()
+            .map(ChapterSlug::from_url)
+            .transpose()
+    }
+    /// Slug of the next chapter, `None` on the last one
+    pub fn next_slug(&self) -> RawKumaResult<Option<ChapterSlug>> {
+        self.next_url
+            .as_ref()
+            .map(ChapterSlug::from_url)
+            .transpose()
+    }
+    pub fn get_ts_reader_script_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.reader.script)
     }
     pub fn get_ts_reader_script_element<'a>(
         element: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
     ) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_ts_reader_script_selector()?;
+        let selector = Self::get_ts_reader_script_selector(profile)?;
         element
             .select(&selector)
             .next()
-            .ok_or(Error::ElementNotFound(String::from(
-                "div.readingnav.rnavbot + script",
-            )))
+            .ok_or(Error::ElementNotFound(profile.reader.script.clone()))
     }
 }
 
 impl<'a> FromElementRef<'a> for TSReaderArgs {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self>
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.reader.script.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        _context: &ParseContext,
+    ) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Days, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl TimeUnit {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().trim_end_matches('s') {
+            "sec" | "second" => Some(Self::Second),
+            "min" | "minute" => Some(Self::Minute),
+            "hour" | "hr" => Some(Self::Hour),
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            "year" | "yr" => Some(Self::Year),
+            _ => None,
+        }
+    }
+}
+
+/// Release date of a chapter, as shown in the chapter list.
+///
+/// Older chapters show a date such as `March 3, 2024`, the freshest ones an
+/// age such as `2 hours ago` which only makes sense against the time the page
+/// was fetched, given to [`ChapterDate::resolve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum ChapterDate {
+    Absolute(NaiveDate),
+    Relative { amount: u32, unit: TimeUnit },
+}
+
+impl ChapterDate {
+    /// Parse `March 3, 2024`, `Mar 3, 2024`, `2024-03-03`, `2 hours ago`, `an hour ago`,
+    /// `just now`, `today` or `yesterday`.
+    pub fn parse(date: &str) -> Option<Self> {
+        Self::parse_with_format(date, None)
+    }
+    /// Parse a date in the chrono `format` of a site, such as `%d.%m.%Y`,
+    /// falling back to the formats of [`ChapterDate::parse`].
+    pub fn parse_with_format(date: &str, format: Option<&str>) -> Option<Self> {
+        let date = date.trim();
+        let formats = format
+            .into_iter()
+            .chain(["%B %d, %Y", "%b %d, %Y", "%Y-%m-%d", "%d/%m/%Y"]);
+        for format in formats {
+            if let Ok(date) = NaiveDate::parse_from_str(date, format) {
+                return Some(Self::Absolute(date));
+            }
+        }
+        let lowercase = date.to_lowercase();
+        match lowercase.as_str() {
+            "just now" | "now" => {
+                return Some(Self::Relative {
+                    amount: 0,
+                    unit: TimeUnit::Second,
+                })
+            }
+            "today" => {
+                return Some(Self::Relative {
+                    amount: 0,
+                    unit: TimeUnit::Day,
+                })
+            }
+            "yesterday" => {
+                return Some(Self::Relative {
+                    amount: 1,
+                    unit: TimeUnit::Day,
+                })
+            }
+            _ => {}
+        }
+        let words: Vec<&str> = lowercase.split_whitespace().collect();
+        match words.as_slice() {
+            [amount, unit, "ago"] => {
+                let amount = match *amount {
+                    "a" | "an" | "one" => 1,
+                    amount => amount.parse::<u32>().ok()?,
+                };
+                Some(Self::Relative {
+                    amount,
+                    unit: TimeUnit::from_name(unit)?,
+                })
+            }
+            _ => None,
+        }
+    }
+    /// Point in time of the release, `reference` being the time the page was fetched.
+    ///
+    /// Absolute dates resolve to midnight in the time zone of `reference`.
+    pub fn resolve<Tz: TimeZone>(&self, reference: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        match *self {
+            Self::Absolute(date) => reference
+                .timezone()
+                .from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .earliest(),
+            Self::Relative { amount, unit } => {
+                let amount_i64 = i64::from(amount);
+                match unit {
+                    TimeUnit::Second => reference
+                        .clone()
+                        .checked_sub_signed(Duration::seconds(amount_i64)),
+                    TimeUnit::Minute => reference
+                        .clone()
+                        .checked_sub_signed(Duration::minutes(amount_i64)),
+                    TimeUnit::Hour => reference
+                        .clone()
+                        .checked_sub_signed(Duration::hours(amount_i64)),
+                    TimeUnit::Day => reference
+                        .clone()
+                        .checked_sub_days(Days::new(u64::from(amount))),
+                    TimeUnit::Week => reference
+                        .clone()
+                        .checked_sub_days(Days::new(u64::from(amount) * 7)),
+                    TimeUnit::Month => reference.clone().checked_sub_months(Months::new(amount)),
+                    TimeUnit::Year => reference
+                        .clone()
+                        .checked_sub_months(Months::new(amount.checked_mul(12)?)),
+                }
+            }
+        }
+    }
+    /// Day of the release, `reference` being the time the page was fetched.
+    pub fn to_naive_date<Tz: TimeZone>(&self, reference: &DateTime<Tz>) -> Option<NaiveDate> {
+        match *self {
+            Self::Absolute(date) => Some(date),
+            Self::Relative { .. } => self.resolve(reference).map(|date| date.date_naive()),
+        }
+    }
+    /// Day of the release, with the relative dates resolved against the current time.
+    pub fn to_naive_date_now(&self) -> Option<NaiveDate> {
+        self.to_naive_date(&Utc::now())
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// What a chapter is besides its number. Extras come after the regular chapter
+/// of the same number.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum ChapterKind {
+    #[default]
+    Regular,
+    Extra,
+    SideStory,
+    Oneshot,
+}
+
+/// Number of a chapter, parsed from labels such as `Chapter 21.1`,
+/// `Vol. 3 Ch. 21`, `Chapter 50.5 Extra`, `Side Story 2`, `Oneshot` or `Chapter 100 [END]`.
+///
+/// Chapters are ordered by their decimal number, kind then volume, a
+/// missing number coming first, so `21.25` comes before `21.5` and `21.50`
+/// is `21.5`. `is_end` is ignored, so `Chapter 100` and `Chapter 100 [END]`
+/// are the same chapter.
+///
+/// ```
+/// use rawkuma_scraper::types::{ChapterKind, ChapterNumber};
+///
+/// let number = ChapterNumber::parse("Vol. 3 Chapter 21.1");
+/// assert_eq!(number.volume, Some(3));
+/// assert_eq!((number.main, number.sub.as_deref()), (Some(21), Some("1")));
+/// assert!(number < ChapterNumber::parse("Chapter 21.25"));
+/// assert_eq!(ChapterNumber::parse("Oneshot").kind, ChapterKind::Oneshot);
+/// assert_eq!(ChapterNumber::parse("Chapter 100"), ChapterNumber::parse("Chapter 100 [END]"));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ChapterNumber {
+    pub main: Option<u32>,
+    /// Digits after the decimal point without the trailing zeros, the `05`
+    /// of `21.05` or the `5` of `21.50`, `None` for `21` and `21.0`
+    pub sub: Option<String>,
+    pub kind: ChapterKind,
+    pub volume: Option<u32>,
+    /// The label marks the last chapter of the series
+    pub is_end: bool,
+}
+
+/// Split a label in runs of letters and runs of digits, keeping a dot between digits.
+fn tokenize(label: &str) -> Vec<String> {
+    let chars: Vec<char> = label.chars().collect();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for (index, c) in chars.iter().enumerate() {
+        let is_number = current.chars().next().is_some_and(|c| c.is_ascii_digit());
+        let continues = if c.is_ascii_digit() {
+            current.is_empty() || is_number
+        } else if *c == '.' {
+            is_number
+                && !current.contains('.')
+                && chars.get(index + 1).is_some_and(|c| c.is_ascii_digit())
+        } else if c.is_alphabetic() {
+            current.is_empty() || !is_number
+        } else {
+            false
+        };
+        if !continues && !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        if c.is_ascii_digit() || c.is_alphabetic() || (*c == '.' && continues) {
+            current.push(*c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Words introducing the chapter number, as in `Chapter 5` or `第5話`
+const NUMBER_MARKERS: [&str; 6] = ["chapter", "chap", "ch", "episode", "ep", "第"];
+
+/// Counters following the number, as in `第5話`
+const COUNTERS: [&str; 3] = ["話", "话", "回"];
+
+/// Words marking the last chapter, right after the number
+const END_MARKERS: [&str; 4] = ["end", "final", "完", "完結"];
+
+/// Kind of a chapter from `words`, the letters of the label around the number
+fn get_kind(words: &str) -> ChapterKind {
+    if words.contains("oneshot") {
+        ChapterKind::Oneshot
+    } else if words.contains("sidestory") || words.contains("番外") {
+        ChapterKind::SideStory
+    } else if ["extra", "bonus", "special", "omake"]
+        .iter()
+        .any(|marker| words.contains(marker))
+    {
+        ChapterKind::Extra
+    } else {
+        ChapterKind::Regular
+    }
+}
+
+/// `21` and `5` of `21.50`, without the trailing zeros of the fraction
+fn split_number(number: &str) -> (Option<u32>, Option<String>) {
+    let (main, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let fraction = fraction.trim_end_matches('0');
+    (
+        main.parse::<u32>().ok(),
+        (!fraction.is_empty()).then(|| fraction.to_string()),
+    )
+}
+
+impl ChapterNumber {
+    /// Parse a chapter label.
+    ///
+    /// The number is the first one following a chapter marker such as `Chapter`,
+    /// `Ch.` or `第`, or else the first one that isn't the volume. The kind is read
+    /// from the two words before the number and the one after it, and the end
+    /// marker from the word right after it, so the title of the chapter is ignored.
+    /// A label without a number is read whole.
+    pub fn parse(label: &str) -> Self {
+        let label = label.to_lowercase();
+        let tokens = tokenize(&label);
+        let is_number = |token: &str| token.starts_with(|c: char| c.is_ascii_digit());
+
+        let mut volume: Option<u32> = None;
+        let mut numbers: Vec<usize> = Vec::new();
+        let mut index = 0;
+        while index < tokens.len() {
+            let token = tokens[index].as_str();
+            let next = tokens.get(index + 1).map(|token| token.as_str());
+            if matches!(token, "vol" | "volume" | "v") {
+                if let Some(value) = next.and_then(|next| next.parse::<u32>().ok()) {
+                    volume = volume.or(Some(value));
+                    index += 2;
+                    continue;
+                }
+            }
+            if is_number(token) {
+                if next == Some("巻") {
+                    volume = volume.or(token.parse::<u32>().ok());
+                    index += 2;
+                    continue;
+                }
+                numbers.push(index);
+            }
+            index += 1;
+        }
+        let number_index = numbers
+            .iter()
+            .find(|index| {
+                index
+                    .checked_sub(1)
+                    .is_some_and(|previous| NUMBER_MARKERS.contains(&tokens[previous].as_str()))
+            })
+            .or(numbers.first())
+            .copied();
+
+        let Some(number_index) = number_index else {
+            let words: String = label.chars().filter(|c| c.is_alphanumeric()).collect();
+            return Self {
+                kind: get_kind(&words),
+                volume,
+                ..Default::default()
+            };
+        };
+        // The word after the number, past a counter such as `話`
+        let mut after = number_index + 1;
+        if tokens
+            .get(after)
+            .is_some_and(|token| COUNTERS.contains(&token.as_str()))
+        {
+            after += 1;
+        }
+        let following = tokens.get(after).map(|token| token.as_str());
+        let mut words: String = tokens[number_index.saturating_sub(2)..number_index]
+            .iter()
+            .filter(|token| !is_number(token))
+            .map(|token| token.as_str())
+            .collect();
+        words.push_str(following.unwrap_or_default());
+        let (main, sub) = split_number(&tokens[number_index]);
+        Self {
+            main,
+            sub,
+            kind: get_kind(&words),
+            volume,
+            is_end: following.is_some_and(|token| END_MARKERS.contains(&token)),
+        }
+    }
+    /// Parse `label`, taking the number from `fallback`, such as the `data-num`
+    /// attribute of the chapter list, when the label has none.
+    pub fn parse_with_fallback(label: &str, fallback: &str) -> Self {
+        let mut number = Self::parse(label);
+        if number.main.is_none() && number.kind != ChapterKind::Oneshot {
+            let fallback = Self::parse(fallback);
+            number.main = fallback.main;
+            number.sub = fallback.sub;
+        }
+        number
+    }
+}
+
+impl ChapterNumber {
+    /// The fields identifying the chapter, in sort order
+    ///
+    /// The fractions compare as strings: without trailing zeros, their
+    /// lexicographic order is the order of their decimal values.
+    fn key(&self) -> (Option<u32>, Option<&str>, ChapterKind, Option<u32>) {
+        (self.main, self.sub.as_deref(), self.kind, self.volume)
+    }
+}
+
+impl PartialEq for ChapterNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ChapterNumber {}
+
+impl PartialOrd for ChapterNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ChapterNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for ChapterNumber {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl Display for ChapterNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(volume) = self.volume {
+            parts.push(format!("Vol. {}", volume));
+        }
+        match self.kind {
+            ChapterKind::Oneshot => parts.push("Oneshot".to_string()),
+            ChapterKind::SideStory => parts.push("Side Story".to_string()),
+            ChapterKind::Regular | ChapterKind::Extra => parts.push("Chapter".to_string()),
+        }
+        match (self.main, &self.sub) {
+            (Some(main), Some(sub)) => parts.push(format!("{}.{}", main, sub)),
+            (Some(main), None) => parts.push(main.to_string()),
+            _ => {}
+        }
+        if self.kind == ChapterKind::Extra {
+            parts.push("Extra".to_string());
+        }
+        if self.is_end {
+            parts.push("[END]".to_string());
+        }
+        f.write_str(parts.join(" ").as_str())
+    }
+}
@@ -1,3 +1,6 @@
+use std::ops::RangeBounds;
+
+use chrono::{DateTime, TimeZone};
 use derive_builder::Builder;
 use reqwest::Url;
 use scraper::{ElementRef, Selector};
@@ -7,7 +10,10 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "getset")]
 use getset::Getters;
 
-use super::{error, FromElementRef, RawKumaResult};
+use super::{
+    error, ChapterDate, ChapterNumber, ChapterSlug, FromElementRef, ParseContext, RawKumaResult,
+};
+use crate::parser::{profile::parse_selector, SelectorProfile};
 
 #[derive(Builder, Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "getset", derive(Getters))]
@@ -18,33 +24,66 @@ pub struct ChapterList {
 }
 
 impl<'a> ChapterList {
-    pub fn get_chapter_list_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div#chapterlist"#)?)
+    pub fn get_chapter_list_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.chapter_list.list)
     }
-    pub fn get_chapter_list_element(data: &'a ElementRef) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_chapter_list_selector()?;
+    pub fn get_chapter_list_element(
+        data: &'a ElementRef,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_chapter_list_selector(profile)?;
         data.select(&selector)
             .next()
             .ok_or(super::error::Error::ElementNotFound(
-                r#"div#chapterlist"#.to_string(),
+                profile.chapter_list.list.clone(),
             ))
     }
-    pub fn get_chapters_elements(data: &'a ElementRef) -> RawKumaResult<Vec<ElementRef<'a>>> {
-        let selector = Chapter::get_data_num_selector()?;
+    pub fn get_chapters_elements(
+        data: &'a ElementRef,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<ElementRef<'a>>> {
+        let selector = Chapter::get_data_num_selector(profile)?;
         RawKumaResult::Ok(data.select(&selector).collect())
     }
+    /// Sort the chapters in reading order by [`ChapterNumber`]
+    pub fn sort_by_number(&mut self) {
+        self.chapters.sort_by(|a, b| a.number.cmp(&b.number));
+    }
+    /// Sort the chapters by [`ChapterNumber`] and keep the first of each number
+    pub fn dedup_by_number(&mut self) {
+        self.chapters.sort_by(|a, b| a.number.cmp(&b.number));
+        self.chapters.dedup_by(|a, b| a.number == b.number);
+    }
+    /// Chapters whose `num` is in `range`, in reading order
+    pub fn get_range(&self, range: impl RangeBounds<f32>) -> Vec<Chapter> {
+        let mut chapters: Vec<Chapter> = self
+            .chapters
+            .iter()
+            .filter(|chapter| range.contains(&chapter.num))
+            .cloned()
+            .collect();
+        chapters.sort_by(|a, b| a.num.total_cmp(&b.num));
+        chapters
+    }
 }
 
 impl<'a> FromElementRef<'a> for ChapterList {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self>
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.chapter_list.list.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
         RawKumaResult::Ok(
             ChapterListBuilder::default()
-                .chapters(Chapter::from_vec_element(&Self::get_chapters_elements(
-                    data,
-                )?)?)
+                .chapters(Chapter::from_vec_element(
+                    &Self::get_chapters_elements(data, context.get_selector_profile())?,
+                    context,
+                )?)
                 .build()?,
         )
     }
@@ -57,140 +96,212 @@ pub struct Chapter {
     #[cfg_attr(feature = "specta", specta(type = String))]
     pub url: Url,
     pub chapter_num: String,
+    /// The date as displayed, such as `March 3, 2024` or `2 hours ago`
     pub chapter_date: String,
+    /// `chapter_date` parsed, `None` when its format is unknown
+    #[serde(default)]
+    #[builder(default)]
+    pub date: Option<ChapterDate>,
+    /// The `data-num` attribute, prefer `number` to sort the chapters
     pub num: f32,
+    /// `chapter_num` parsed, with `data-num` as fallback
+    #[serde(default)]
+    #[builder(default)]
+    pub number: ChapterNumber,
     #[cfg_attr(feature = "specta", specta(type = String))]
     pub download_link: Url,
 }
 
 impl<'a> Chapter {
-    pub fn get_data_num_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse("li[data-num]")?)
+    /// Release time of the chapter, relative dates being resolved against `reference`,
+    /// the time the chapter list was fetched.
+    pub fn get_release_time<Tz: TimeZone>(&self, reference: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        self.date?.resolve(reference)
     }
-    pub fn get_eph_num_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse("div.eph-num")?)
+    /// Slug of the chapter, read from its url
+    pub fn slug(&self) -> RawKumaResult<ChapterSlug> {
+        ChapterSlug::from_url(&self.url)
     }
-    pub fn get_dload_a_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse("a.dload")?)
+    pub fn get_data_num_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.chapter_list.chapter)
     }
-    fn get_a_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse("a")?)
+    pub fn get_eph_num_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.chapter_list.eph_num)
     }
-    fn get_chapternum_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(".chapternum")?)
+    pub fn get_dload_a_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.chapter_list.download)
     }
-    fn get_chapterdate_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(".chapterdate")?)
+    fn get_a_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.chapter_list.link)
+    }
+    fn get_chapternum_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.chapter_list.number)
+    }
+    fn get_chapterdate_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.chapter_list.date)
     }
 
-    pub fn get_eph_num_element(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_eph_num_selector()?;
+    pub fn get_eph_num_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_eph_num_selector(profile)?;
         data.select(&selector)
             .next()
             .ok_or(super::error::Error::ElementNotFound(
-                "div.eph-num".to_string(),
+                profile.chapter_list.eph_num.clone(),
             ))
     }
-    pub fn get_dload_element(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_dload_a_selector()?;
+    pub fn get_dload_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_dload_a_selector(profile)?;
         data.select(&selector)
             .next()
-            .ok_or(super::error::Error::ElementNotFound("a.dload".to_string()))
+            .ok_or(super::error::Error::ElementNotFound(
+                profile.chapter_list.download.clone(),
+            ))
     }
-    pub fn get_a_ephnum_element(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_a_selector()?;
-        let ephnum = Self::get_eph_num_element(data)?;
+    pub fn get_a_ephnum_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_a_selector(profile)?;
+        let ephnum = Self::get_eph_num_element(data, profile)?;
         ephnum
             .select(&selector)
             .next()
             .ok_or(super::error::Error::ElementNotFoundInNested {
-                element: "a".to_string(),
-                parent: "div.eph-num".to_string(),
+                element: profile.chapter_list.link.clone(),
+                parent: profile.chapter_list.eph_num.clone(),
             })
     }
-    pub fn get_chapterdate_element(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_chapterdate_selector()?;
-        let a_ephnum = Self::get_a_ephnum_element(data)?;
+    /// Selector of the link holding the chapter number and date, for the errors
+    fn get_link_path(profile: &SelectorProfile) -> String {
+        format!(
+            "{} {}",
+            profile.chapter_list.eph_num, profile.chapter_list.link
+        )
+    }
+    pub fn get_chapterdate_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_chapterdate_selector(profile)?;
+        let a_ephnum = Self::get_a_ephnum_element(data, profile)?;
         a_ephnum
             .select(&selector)
             .next()
             .ok_or(super::error::Error::ElementNotFoundInNested {
-                element: ".chapternum".to_string(),
-                parent: "a > .eph-num".to_string(),
+                element: profile.chapter_list.date.clone(),
+                parent: Self::get_link_path(profile),
             })
     }
-    pub fn get_chapternum_element(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_chapternum_selector()?;
-        let a_ephnum = Self::get_a_ephnum_element(data)?;
+    pub fn get_chapternum_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_chapternum_selector(profile)?;
+        let a_ephnum = Self::get_a_ephnum_element(data, profile)?;
         a_ephnum
             .select(&selector)
             .next()
             .ok_or(super::error::Error::ElementNotFoundInNested {
-                element: ".chapternum".to_string(),
-                parent: "a > .eph-num".to_string(),
+                element: profile.chapter_list.number.clone(),
+                parent: Self::get_link_path(profile),
             })
     }
 
-    pub fn get_a_ephnum_data(data: &'a ElementRef<'a>) -> RawKumaResult<Url> {
-        let a_ephnum = Self::get_a_ephnum_element(data)?;
-        Ok(Url::parse(a_ephnum.value().attr("href").ok_or(
+    /// The url of the chapter, resolved against the url of the page
+    pub fn get_a_ephnum_data(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Url> {
+        let profile = context.get_selector_profile();
+        let a_ephnum = Self::get_a_ephnum_element(data, profile)?;
+        context.resolve_url(a_ephnum.value().attr("href").ok_or(
             super::error::Error::AttributeNotFound {
                 name: "href".to_string(),
-                element: "a > .eph-num".to_string(),
+                element: Self::get_link_path(profile),
             },
-        )?)?)
+        )?)
     }
-    pub fn get_chapternum_data(data: &'a ElementRef<'a>) -> RawKumaResult<String> {
-        let chapternum = Self::get_chapternum_element(data)?;
+    pub fn get_chapternum_data(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<String> {
+        let chapternum = Self::get_chapternum_element(data, profile)?;
         Ok(chapternum
             .text()
             .next()
             .map(|d| d.to_string())
             .unwrap_or(String::new()))
     }
-    pub fn get_chapterdate_data(data: &'a ElementRef<'a>) -> RawKumaResult<String> {
-        let chapterdate = Self::get_chapterdate_element(data)?;
+    pub fn get_chapterdate_data(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<String> {
+        let chapterdate = Self::get_chapterdate_element(data, profile)?;
         Ok(chapterdate
             .text()
             .next()
             .map(|d| d.to_string())
             .unwrap_or(String::new()))
     }
-    pub fn get_data_num(data: &'a ElementRef<'a>) -> RawKumaResult<f32> {
-        Ok(data
-            .value()
+    pub fn get_data_num_text(data: &'a ElementRef<'a>) -> RawKumaResult<&'a str> {
+        data.value()
             .attr("data-num")
             .ok_or(error::Error::AttributeNotFound {
                 name: "data-num".to_string(),
                 element: data.html(),
-            })?
-            .parse::<f32>()?)
-    }
-    pub fn get_dload_data(data: &'a ElementRef<'a>) -> RawKumaResult<Url> {
-        let element = Self::get_dload_element(data)?;
-        Ok(element
-            .value()
-            .attr("href")
-            .ok_or(error::Error::AttributeNotFound {
+            })
+    }
+    pub fn get_data_num(data: &'a ElementRef<'a>) -> RawKumaResult<f32> {
+        Ok(Self::get_data_num_text(data)?.parse::<f32>()?)
+    }
+    /// The download link of the chapter, resolved against the url of the page
+    pub fn get_dload_data(data: &'a ElementRef<'a>, context: &ParseContext) -> RawKumaResult<Url> {
+        let profile = context.get_selector_profile();
+        let element = Self::get_dload_element(data, profile)?;
+        context.resolve_url(element.value().attr("href").ok_or(
+            error::Error::AttributeNotFound {
                 name: "href".to_string(),
-                element: "a.dload".to_string(),
-            })?
-            .parse::<Url>()?)
+                element: profile.chapter_list.download.clone(),
+            },
+        )?)
     }
 }
 
 impl<'a> FromElementRef<'a> for Chapter {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self>
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.chapter_list.chapter.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
+        let profile = context.get_selector_profile();
+        let chapter_date = Self::get_chapterdate_data(data, profile)?;
+        let chapter_num = Self::get_chapternum_data(data, profile)?;
         RawKumaResult::Ok(
             ChapterBuilder::default()
-                .chapter_date(Self::get_chapterdate_data(data)?)
-                .chapter_num(Self::get_chapternum_data(data)?)
-                .download_link(Self::get_dload_data(data)?)
+                .date(ChapterDate::parse_with_format(
+                    &chapter_date,
+                    context.get_date_format(),
+                ))
+                .chapter_date(chapter_date)
+                .number(ChapterNumber::parse_with_fallback(
+                    &chapter_num,
+                    Self::get_data_num_text(data)?,
+                ))
+                .chapter_num(chapter_num)
+                .download_link(Self::get_dload_data(data, context)?)
                 .num(Self::get_data_num(data)?)
-                .url(Self::get_a_ephnum_data(data)?)
+                .url(Self::get_a_ephnum_data(data, context)?)
                 .build()?,
         )
     }
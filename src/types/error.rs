@@ -1,11 +1,15 @@
 use std::{
     fmt::Display,
+    io::ErrorKind,
     num::{ParseFloatError, ParseIntError},
 };
 
 use derive_builder::UninitializedFieldError;
+use reqwest::StatusCode;
 use thiserror::Error;
 
+use crate::RetryPolicy;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -20,8 +24,9 @@ pub enum Error {
     ElementNotFoundInNested { element: String, parent: String },
     #[error("Attribute '{}' not found on the element {}", name, element)]
     AttributeNotFound { name: String, element: String },
-    #[error(transparent)]
-    ScraperParseError(#[from] scraper::error::SelectorErrorKind<'static>),
+    /// The selector error is kept as a string so the error is `Send` and `Sync`
+    #[error("{0}")]
+    ScraperParseError(String),
     #[error(transparent)]
     BuilderError(#[from] BuilderError),
     #[error("Text content is not found")]
@@ -36,6 +41,88 @@ pub enum Error {
     SelectorErrorKind(String),
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
+    #[error("The chapter has no image source")]
+    NoImageSource,
+    #[error("{} responded with the HTTP status {}: {}", url, status, body_snippet)]
+    HttpStatus {
+        url: url::Url,
+        status: u16,
+        /// Start of the response body, to help understanding the failure
+        body_snippet: String,
+    },
+    #[error("{url} was not found")]
+    NotFound { url: url::Url },
+    #[error("{url} answered with an anti-bot challenge page")]
+    ChallengePage { url: url::Url },
+    #[error("'{0}' is not a valid slug or rawkuma url")]
+    InvalidSlug(String),
+    #[error("{0} is not in the cassette")]
+    NotRecorded(url::Url),
+    #[error("Invalid HTTP status code {0}")]
+    InvalidStatusCode(u16),
+    #[cfg(feature = "cassette")]
+    #[error(transparent)]
+    Base64DecodeError(#[from] base64::DecodeError),
+    #[error("A source with the id '{0}' is already registered")]
+    DuplicateSource(String),
+    #[error("The source '{0}' did not answer within {1:?}")]
+    SourceTimeout(String, std::time::Duration),
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    TomlError(#[from] toml::de::Error),
+    #[error("Cannot detect the image format of {0}")]
+    UnknownImageFormat(url::Url),
+    #[error("The {mime_type} image {url} is not supported")]
+    UnsupportedImageFormat { url: url::Url, mime_type: String },
+    #[error("There is no chapter page to export")]
+    NoChapters,
+    #[cfg(any(feature = "cbz", feature = "epub"))]
+    #[error(transparent)]
+    ZipError(#[from] zip::result::ZipError),
+    #[cfg(feature = "pdf")]
+    #[error(transparent)]
+    LopdfError(#[from] lopdf::Error),
+    #[cfg(feature = "pdf")]
+    #[error(transparent)]
+    ImageError(#[from] image::ImageError),
+}
+
+/// For the conversions that cannot fail, such as a [`MangaSlug`](super::MangaSlug) into itself
+impl From<std::convert::Infallible> for Error {
+    fn from(e: std::convert::Infallible) -> Self {
+        match e {}
+    }
+}
+
+impl<'a> From<scraper::error::SelectorErrorKind<'a>> for Error {
+    fn from(e: scraper::error::SelectorErrorKind<'a>) -> Self {
+        Self::ScraperParseError(e.to_string())
+    }
+}
+
+impl Error {
+    /// Whether the failure is transient, so the same request may succeed later:
+    /// connection errors, timeouts, `408`, `429` and `5xx` statuses.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::ReqwestError(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(RetryPolicy::is_retryable_status)
+            }
+            Error::Io(e) => matches!(
+                e.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::ConnectionRefused
+                    | ErrorKind::TimedOut
+            ),
+            Error::HttpStatus { status, .. } => {
+                StatusCode::from_u16(*status).is_ok_and(RetryPolicy::is_retryable_status)
+            }
+            _ => false,
+        }
+    }
 }
 
 #[doc = "Error type for Rawkuma Error"]
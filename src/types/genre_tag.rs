@@ -7,7 +7,8 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "getset")]
 use getset::Getters;
 
-use super::{error::Error, FromElementRef, RawKumaResult};
+use super::{error::Error, FromElementRef, ParseContext, RawKumaResult};
+use crate::parser::{profile::parse_selector, SelectorProfile};
 
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "getset", derive(Getters))]
@@ -21,43 +22,56 @@ pub struct MgenTag {
 }
 
 impl<'a> MgenTag {
-    pub fn get_mgen_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#".mgen"#)?)
+    pub fn get_mgen_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.genres.container)
     }
-    pub fn get_tag_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"a[rel="tag"]"#)?)
+    pub fn get_tag_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.genres.tag)
     }
-    pub fn get_mgen_element(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        let selector = Self::get_mgen_selector()?;
+    pub fn get_mgen_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        let selector = Self::get_mgen_selector(profile)?;
         data.select(&selector)
             .next()
-            .ok_or(Error::ElementNotFound(".mgen".to_string()))
+            .ok_or(Error::ElementNotFound(profile.genres.container.clone()))
     }
-    pub fn get_tag_elements(data: &'a ElementRef<'a>) -> RawKumaResult<Vec<ElementRef<'a>>> {
-        let selector = Self::get_tag_selector()?;
-        let elements: Vec<ElementRef<'a>> =
-            Self::get_mgen_element(data)?.select(&selector).collect();
+    pub fn get_tag_elements(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<ElementRef<'a>>> {
+        let selector = Self::get_tag_selector(profile)?;
+        let elements: Vec<ElementRef<'a>> = Self::get_mgen_element(data, profile)?
+            .select(&selector)
+            .collect();
         RawKumaResult::Ok(elements)
     }
-    pub fn get_tags_elements_data(data: &'a ElementRef<'a>) -> RawKumaResult<Vec<Self>> {
-        let elements = Self::get_tag_elements(data)?;
-        Self::from_vec_element(&elements)
+    pub fn get_tags_elements_data(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Vec<Self>> {
+        let elements = Self::get_tag_elements(data, context.get_selector_profile())?;
+        Self::from_vec_element(&elements, context)
     }
 }
 
 impl<'a> FromElementRef<'a> for MgenTag {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self>
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.genres.tag.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self>
     where
         Self: Sized,
     {
-        let url = data
-            .value()
-            .attr("href")
-            .ok_or(Error::AttributeNotFound {
+        let url =
+            context.resolve_url(data.value().attr("href").ok_or(Error::AttributeNotFound {
                 name: "href".to_string(),
-                element: "a".to_string(),
-            })?
-            .parse::<Url>()?;
+                element: context.get_selector_profile().genres.tag.clone(),
+            })?)?;
         let name = data
             .text()
             .next()
@@ -25,9 +25,9 @@ pub struct RawKumaHomeData {
 impl<'a> FromHtmlParser<'a, RawKumaHomeParser<'a>> for RawKumaHomeData {
     fn from(home: RawKumaHomeParser) -> RawKumaResult<Self> {
         let data = RawKumaHomeDataBuilder::default()
-            .popular_title(home.get_popular_today())
-            .recommandation(home.get_recommandation())
-            .latest_update(home.get_latest())
+            .popular_title(home.get_popular_today()?)
+            .recommandation(home.get_recommandation()?)
+            .latest_update(home.get_latest()?)
             .build()?;
         RawKumaResult::Ok(data)
     }
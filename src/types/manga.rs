@@ -8,8 +8,10 @@ use getset::{Getters, Setters};
 use crate::enums::manga::{Genre, Order, Status, Type};
 
 mod details;
+mod list;
 
 pub use details::{RawKumaMangaDetailData, RawKumaMangaDetailDataBuilder};
+pub use list::{RawKumaMangaList, RawKumaMangaListBuilder};
 
 use super::ToUrlParam;
 
@@ -23,7 +25,6 @@ pub struct MangaListParameter {
     status: Status,
     genre: Vec<Genre>,
     order: Order,
-    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: Type,
 }
 
@@ -0,0 +1,33 @@
+use derive_builder::Builder;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "getset")]
+use getset::Getters;
+
+use crate::parser::manga::RawKumaMangaListParser;
+
+use crate::types::{BsxTitleData, FromHtmlParser, Pagination, RawKumaResult};
+
+#[derive(Serialize, Deserialize, Clone, Builder, Default)]
+#[cfg_attr(feature = "getset", derive(Getters))]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[builder(build_fn(error = "crate::types::error::BuilderError"))]
+pub struct RawKumaMangaList {
+    pub result: Vec<BsxTitleData>,
+    pub pagination: Pagination,
+}
+
+impl<'a> FromHtmlParser<'a, RawKumaMangaListParser<'a>> for RawKumaMangaList {
+    fn from(parser: RawKumaMangaListParser<'a>) -> RawKumaResult<Self>
+    where
+        Self: Sized,
+    {
+        RawKumaResult::Ok(
+            RawKumaMangaListBuilder::default()
+                .result(parser.get_bsx_results()?)
+                .pagination(parser.get_pagination()?)
+                .build()?,
+        )
+    }
+}
@@ -0,0 +1,193 @@
+use derive_builder::Builder;
+use reqwest::Url;
+use scraper::{ElementRef, Selector};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "getset")]
+use getset::Getters;
+
+use super::{error::Error, RawKumaResult};
+use crate::parser::{profile::parse_selector, SelectorProfile};
+
+#[derive(Builder, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "getset", derive(Getters))]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[builder(build_fn(error = "crate::types::error::BuilderError"))]
+pub struct Pagination {
+    pub current_page: u32,
+    pub total_pages: Option<u32>,
+    pub has_next: bool,
+    #[cfg_attr(feature = "specta", specta(type = Option<String>))]
+    pub next_url: Option<Url>,
+    #[cfg_attr(feature = "specta", specta(type = Option<String>))]
+    pub previous_url: Option<Url>,
+}
+
+impl<'a> Pagination {
+    pub fn get_hpage_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.pagination.hpage)
+    }
+    pub fn get_hpage_next_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.pagination.hpage_next)
+    }
+    pub fn get_hpage_previous_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.pagination.hpage_previous)
+    }
+
+    pub fn get_pagination_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.pagination.pagination)
+    }
+    pub fn get_page_numbers_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.pagination.page_numbers)
+    }
+
+    pub fn get_hpage_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> Option<ElementRef<'a>> {
+        data.select(&(Self::get_hpage_selector(profile).ok()?))
+            .next()
+    }
+
+    pub fn get_pagination_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> Option<ElementRef<'a>> {
+        data.select(&(Self::get_pagination_selector(profile).ok()?))
+            .next()
+    }
+
+    /// Read the page number from a listing url.
+    ///
+    /// The theme uses either a `page` query parameter (`/manga/?page=2`)
+    /// or a `/page/<n>/` path segment (`/page/2/?s=query`).
+    pub fn get_page_from_url(url: &Url) -> Option<u32> {
+        if let Some((_, page)) = url.query_pairs().find(|(key, _)| key == "page") {
+            return page.parse::<u32>().ok();
+        }
+        let mut segments = url.path_segments()?;
+        segments.find(|s| *s == "page")?;
+        segments.next()?.parse::<u32>().ok()
+    }
+
+    /// The url of the link `element`, matched by the selectors of `path`
+    fn get_link_data(element: &ElementRef, path: &[&str], page_url: &Url) -> RawKumaResult<Url> {
+        let href = element
+            .value()
+            .attr("href")
+            .ok_or(Error::AttributeNotFound {
+                name: "href".to_string(),
+                element: path.join(" "),
+            })?;
+        RawKumaResult::Ok(page_url.join(href)?)
+    }
+
+    /// Parse the `div.hpage` Previous/Next block used by the `/manga/` listing.
+    ///
+    /// The links are relative to the listing, so `page_url` is required to resolve them.
+    /// A page without the block is considered as the only page.
+    pub fn from_hpage(
+        data: &'a ElementRef<'a>,
+        page_url: &Url,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Self> {
+        let current_page = Self::get_page_from_url(page_url).unwrap_or(1);
+        let selectors = &profile.pagination;
+        let (next_url, previous_url) = match Self::get_hpage_element(data, profile) {
+            None => (None, None),
+            Some(hpage) => (
+                hpage
+                    .select(&(Self::get_hpage_next_selector(profile)?))
+                    .next()
+                    .map(|a| {
+                        Self::get_link_data(
+                            &a,
+                            &[&selectors.hpage, &selectors.hpage_next],
+                            page_url,
+                        )
+                    })
+                    .transpose()?,
+                hpage
+                    .select(&(Self::get_hpage_previous_selector(profile)?))
+                    .next()
+                    .map(|a| {
+                        Self::get_link_data(
+                            &a,
+                            &[&selectors.hpage, &selectors.hpage_previous],
+                            page_url,
+                        )
+                    })
+                    .transpose()?,
+            ),
+        };
+        RawKumaResult::Ok(
+            PaginationBuilder::default()
+                .current_page(current_page)
+                .total_pages(None)
+                .has_next(next_url.is_some())
+                .next_url(next_url)
+                .previous_url(previous_url)
+                .build()?,
+        )
+    }
+
+    /// Parse the numbered `div.pagination` block used by the search pages.
+    ///
+    /// It looks like `1 2 … 10 Next »` where the current page is a `span.current`
+    /// and the others are links.
+    /// A page without the block is considered as the only page.
+    pub fn from_page_numbers(
+        data: &'a ElementRef<'a>,
+        page_url: &Url,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Self> {
+        let pagination = match Self::get_pagination_element(data, profile) {
+            None => {
+                return RawKumaResult::Ok(
+                    PaginationBuilder::default()
+                        .current_page(Self::get_page_from_url(page_url).unwrap_or(1))
+                        .total_pages(Some(1))
+                        .has_next(false)
+                        .next_url(None)
+                        .previous_url(None)
+                        .build()?,
+                )
+            }
+            Some(d) => d,
+        };
+        let mut current_page: Option<u32> = None;
+        let mut total_pages: Option<u32> = None;
+        let mut next_url: Option<Url> = None;
+        let mut previous_url: Option<Url> = None;
+        let path = [
+            profile.pagination.pagination.as_str(),
+            profile.pagination.page_numbers.as_str(),
+        ];
+        for element in pagination.select(&(Self::get_page_numbers_selector(profile)?)) {
+            let classes: Vec<&str> = element.value().classes().collect();
+            if classes.contains(&"next") {
+                next_url = Some(Self::get_link_data(&element, &path, page_url)?);
+            } else if classes.contains(&"prev") {
+                previous_url = Some(Self::get_link_data(&element, &path, page_url)?);
+            } else if let Ok(number) = element.text().collect::<String>().trim().parse::<u32>() {
+                if classes.contains(&"current") {
+                    current_page = Some(number);
+                }
+                total_pages = Some(total_pages.map_or(number, |total| total.max(number)));
+            }
+        }
+        let current_page = current_page
+            .or(Self::get_page_from_url(page_url))
+            .unwrap_or(1);
+        RawKumaResult::Ok(
+            PaginationBuilder::default()
+                .current_page(current_page)
+                .total_pages(total_pages)
+                .has_next(next_url.is_some())
+                .next_url(next_url)
+                .previous_url(previous_url)
+                .build()?,
+        )
+    }
+}
@@ -0,0 +1,163 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc, sync::Arc};
+
+use reqwest::Url;
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+
+use super::{error::Error, RawKumaResult};
+use crate::{constant::BASE_URL, parser::SelectorProfile};
+
+/// Length of the element snippet kept in a [`ParseWarning`]
+pub const ELEMENT_SNIPPET_LENGTH: usize = 200;
+
+/// How the parsers handle an item of a list that cannot be parsed,
+/// such as a single broken card of a search result.
+///
+/// Lenient by default, so a broken card is skipped as it always was.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail with the first error
+    Strict,
+    /// Skip the item and record a [`ParseWarning`]
+    #[default]
+    Lenient,
+}
+
+/// An item skipped by a lenient parse.
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// Selector of the skipped item, such as `div[class="bsx"]`
+    pub selector: String,
+    /// Start of the HTML of the skipped item
+    pub element: String,
+    pub error: Error,
+}
+
+impl ParseWarning {
+    pub fn new(selector: &str, element: &ElementRef, error: Error) -> Self {
+        Self {
+            selector: selector.to_string(),
+            element: element
+                .html()
+                .chars()
+                .take(ELEMENT_SNIPPET_LENGTH)
+                .collect(),
+            error,
+        }
+    }
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} skipped: {} ({})",
+            self.selector, self.error, self.element
+        )
+    }
+}
+
+/// Called by the client for every warning of a lenient parse
+pub type ParseWarningHandler = Arc<dyn Fn(&ParseWarning) + Send + Sync>;
+
+/// Parsed data, with the warnings of a lenient parse.
+#[derive(Debug)]
+pub struct Parsed<T> {
+    pub data: T,
+    /// Always empty in [`ParseMode::Strict`]
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// The settings of a parse, such as its [`ParseMode`], [`SelectorProfile`]
+/// and the url of the page, and the warnings collected so far.
+///
+/// Clones share the warnings.
+#[derive(Clone, Default)]
+pub struct ParseContext {
+    mode: ParseMode,
+    selector_profile: Arc<SelectorProfile>,
+    date_format: Option<String>,
+    page_url: Option<Url>,
+    warnings: Rc<RefCell<Vec<ParseWarning>>>,
+}
+
+impl From<ParseMode> for ParseContext {
+    fn from(mode: ParseMode) -> Self {
+        Self::new(mode)
+    }
+}
+
+impl ParseContext {
+    pub fn new(mode: ParseMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+    /// Parse with `profile` instead of the default [`SelectorProfile`]
+    pub fn with_selector_profile(mut self, profile: Arc<SelectorProfile>) -> Self {
+        self.selector_profile = profile;
+        self
+    }
+    /// Parse the chapter dates with the chrono `format` first, see [`super::ChapterDate::parse_with_format`]
+    pub fn with_date_format(mut self, format: Option<String>) -> Self {
+        self.date_format = format;
+        self
+    }
+    /// Resolve the links of the page against `page_url`, the url it was fetched from
+    pub fn with_page_url(mut self, page_url: Url) -> Self {
+        self.page_url = Some(page_url);
+        self
+    }
+    pub fn get_mode(&self) -> ParseMode {
+        self.mode
+    }
+    pub fn get_selector_profile(&self) -> &SelectorProfile {
+        &self.selector_profile
+    }
+    pub fn get_date_format(&self) -> Option<&str> {
+        self.date_format.as_deref()
+    }
+    pub fn get_page_url(&self) -> Option<&Url> {
+        self.page_url.as_ref()
+    }
+    /// Resolve `href` against the url of the page, or [`BASE_URL`] when it isn't set
+    pub fn resolve_url(&self, href: &str) -> RawKumaResult<Url> {
+        match &self.page_url {
+            Some(page_url) => RawKumaResult::Ok(page_url.join(href)?),
+            None => RawKumaResult::Ok(Url::parse(BASE_URL)?.join(href)?),
+        }
+    }
+    /// Return the parsed item, or in lenient mode record the error of the item
+    /// matched by `selector` and return `None`.
+    pub fn tolerate<T>(
+        &self,
+        selector: &str,
+        element: &ElementRef,
+        result: RawKumaResult<T>,
+    ) -> RawKumaResult<Option<T>> {
+        match (result, self.mode) {
+            (Ok(data), _) => RawKumaResult::Ok(Some(data)),
+            (Err(error), ParseMode::Strict) => RawKumaResult::Err(error),
+            (Err(error), ParseMode::Lenient) => {
+                self.warnings
+                    .borrow_mut()
+                    .push(ParseWarning::new(selector, element, error));
+                RawKumaResult::Ok(None)
+            }
+        }
+    }
+    /// Remove and return the warnings collected so far
+    pub fn take_warnings(&self) -> Vec<ParseWarning> {
+        self.warnings.take()
+    }
+    /// Wrap `data` with the warnings collected so far
+    pub fn finish<T>(&self, data: T) -> Parsed<T> {
+        Parsed {
+            data,
+            warnings: self.take_warnings(),
+        }
+    }
+}
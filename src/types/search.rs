@@ -7,7 +7,7 @@ use getset::Getters;
 
 use crate::parser::search::RawKumaSearchParser;
 
-use super::{BsxTitleData, FromHtmlParser, RawKumaResult};
+use super::{BsxTitleData, FromHtmlParser, Pagination, RawKumaResult};
 
 #[derive(Default, Clone, Builder, Serialize, Deserialize)]
 #[cfg_attr(feature = "getset", derive(Getters))]
@@ -15,6 +15,7 @@ use super::{BsxTitleData, FromHtmlParser, RawKumaResult};
 #[builder(build_fn(error = "crate::types::error::BuilderError"))]
 pub struct RawKumaSearch {
     pub result: Vec<BsxTitleData>,
+    pub pagination: Pagination,
 }
 
 impl<'a> FromHtmlParser<'a, RawKumaSearchParser<'a>> for RawKumaSearch {
@@ -25,6 +26,7 @@ impl<'a> FromHtmlParser<'a, RawKumaSearchParser<'a>> for RawKumaSearch {
         RawKumaResult::Ok(
             RawKumaSearchBuilder::default()
                 .result(parser.get_bsx_results()?)
+                .pagination(parser.get_pagination()?)
                 .build()?,
         )
     }
@@ -0,0 +1,237 @@
+use std::{fmt::Display, str::FromStr};
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use super::{error::Error, RawKumaResult};
+
+/// First path segments of the theme's pages that are neither a series nor a chapter
+const RESERVED_SEGMENTS: [&str; 13] = [
+    "manga",
+    "page",
+    "genres",
+    "genre",
+    "tag",
+    "author",
+    "bookmark",
+    "az-list",
+    "feed",
+    "wp-admin",
+    "wp-content",
+    "wp-json",
+    "wp-login.php",
+];
+
+/// Whether `slug` is a single, non-empty, url path segment that isn't reserved
+fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && !RESERVED_SEGMENTS.contains(&slug)
+        && !slug
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '/' | '?' | '#' | '\\'))
+}
+
+/// Path segments of `url` without the empty ones
+fn get_segments(url: &Url) -> Vec<&str> {
+    url.path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// Path segments of `url` after those of `api_url`, when `url` is a page of the site at `api_url`
+fn get_site_segments<'a>(url: &'a Url, api_url: &Url) -> Option<Vec<&'a str>> {
+    if url.host_str() != api_url.host_str()
+        || url.port_or_known_default() != api_url.port_or_known_default()
+    {
+        return None;
+    }
+    let segments = get_segments(url);
+    let base = get_segments(api_url);
+    segments
+        .starts_with(&base)
+        .then(|| segments[base.len()..].to_vec())
+}
+
+/// Identifier of a series, the `<slug>` of `https://rawkuma.com/manga/<slug>/`.
+///
+/// It parses from the bare slug, from the `manga/<slug>/` path, with or without
+/// the slashes, or from the full url of the series page.
+///
+/// ```
+/// use rawkuma_scraper::types::MangaSlug;
+///
+/// let slug: MangaSlug = "https://rawkuma.com/manga/kanojo-okarishimasu/".parse()?;
+/// assert_eq!(slug.as_str(), "kanojo-okarishimasu");
+/// assert_eq!(slug, "/manga/kanojo-okarishimasu".parse()?);
+/// # Ok::<(), rawkuma_scraper::types::error::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(try_from = "String", into = "String")]
+pub struct MangaSlug(String);
+
+impl MangaSlug {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+    fn from_segments(segments: &[&str]) -> Option<Self> {
+        match segments {
+            ["manga", slug] if is_valid_slug(slug) => Some(Self(slug.to_string())),
+            _ => None,
+        }
+    }
+    /// Read the slug of a series page url, `/manga/<slug>/`, on any host.
+    ///
+    /// Prefer [`MangaSlug::from_source_url`] when the site is known.
+    pub fn from_url(url: &Url) -> RawKumaResult<Self> {
+        Self::from_segments(&get_segments(url)).ok_or(Error::InvalidSlug(url.to_string()))
+    }
+    /// Read the slug of a series page url of the site at `api_url`,
+    /// rejecting the urls of other hosts
+    pub fn from_source_url(url: &Url, api_url: &Url) -> RawKumaResult<Self> {
+        get_site_segments(url, api_url)
+            .and_then(|segments| Self::from_segments(&segments))
+            .ok_or(Error::InvalidSlug(url.to_string()))
+    }
+    /// Parse a bare slug or path like [`FromStr`], or a full url with [`MangaSlug::from_source_url`]
+    pub fn parse_on(s: &str, api_url: &Url) -> RawKumaResult<Self> {
+        match Url::parse(s) {
+            Ok(url) => Self::from_source_url(&url, api_url),
+            Err(_) => s.parse(),
+        }
+    }
+    /// Url of the series page on the site at `api_url`
+    pub fn to_url(&self, api_url: &Url) -> RawKumaResult<Url> {
+        RawKumaResult::Ok(api_url.join(format!("manga/{}/", self.0).as_str())?)
+    }
+}
+
+impl FromStr for MangaSlug {
+    type Err = Error;
+    fn from_str(s: &str) -> RawKumaResult<Self> {
+        if let Ok(url) = Url::parse(s) {
+            return Self::from_url(&url);
+        }
+        let path = s.trim().trim_matches('/');
+        let slug = path.strip_prefix("manga/").unwrap_or(path);
+        if is_valid_slug(slug) {
+            RawKumaResult::Ok(Self(slug.to_string()))
+        } else {
+            RawKumaResult::Err(Error::InvalidSlug(s.to_string()))
+        }
+    }
+}
+
+/// Identifier of a chapter, the `<slug>` of `https://rawkuma.com/<slug>/`.
+///
+/// It parses from the bare slug, with or without the slashes, or from the full
+/// url of the reader page.
+///
+/// ```
+/// use rawkuma_scraper::types::ChapterSlug;
+///
+/// let slug: ChapterSlug = "https://rawkuma.com/kanojo-okarishimasu-chapter-321/".parse()?;
+/// assert_eq!(slug.as_str(), "kanojo-okarishimasu-chapter-321");
+/// # Ok::<(), rawkuma_scraper::types::error::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(try_from = "String", into = "String")]
+pub struct ChapterSlug(String);
+
+impl ChapterSlug {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+    fn from_segments(segments: &[&str]) -> Option<Self> {
+        match segments {
+            [slug] if is_valid_slug(slug) => Some(Self(slug.to_string())),
+            _ => None,
+        }
+    }
+    /// Read the slug of a reader page url, `/<slug>/`, on any host.
+    ///
+    /// Prefer [`ChapterSlug::from_source_url`] when the site is known.
+    pub fn from_url(url: &Url) -> RawKumaResult<Self> {
+        Self::from_segments(&get_segments(url)).ok_or(Error::InvalidSlug(url.to_string()))
+    }
+    /// Read the slug of a reader page url of the site at `api_url`,
+    /// rejecting the urls of other hosts
+    pub fn from_source_url(url: &Url, api_url: &Url) -> RawKumaResult<Self> {
+        get_site_segments(url, api_url)
+            .and_then(|segments| Self::from_segments(&segments))
+            .ok_or(Error::InvalidSlug(url.to_string()))
+    }
+    /// Parse a bare slug like [`FromStr`], or a full url with [`ChapterSlug::from_source_url`]
+    pub fn parse_on(s: &str, api_url: &Url) -> RawKumaResult<Self> {
+        match Url::parse(s) {
+            Ok(url) => Self::from_source_url(&url, api_url),
+            Err(_) => s.parse(),
+        }
+    }
+    /// Url of the reader page on the site at `api_url`
+    pub fn to_url(&self, api_url: &Url) -> RawKumaResult<Url> {
+        RawKumaResult::Ok(api_url.join(format!("{}/", self.0).as_str())?)
+    }
+}
+
+impl FromStr for ChapterSlug {
+    type Err = Error;
+    fn from_str(s: &str) -> RawKumaResult<Self> {
+        if let Ok(url) = Url::parse(s) {
+            return Self::from_url(&url);
+        }
+        let slug = s.trim().trim_matches('/');
+        if is_valid_slug(slug) {
+            RawKumaResult::Ok(Self(slug.to_string()))
+        } else {
+            RawKumaResult::Err(Error::InvalidSlug(s.to_string()))
+        }
+    }
+}
+
+macro_rules! impl_slug_conversions {
+    ($slug:ident) => {
+        impl Display for $slug {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.0.as_str())
+            }
+        }
+        impl AsRef<str> for $slug {
+            fn as_ref(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+        impl TryFrom<&str> for $slug {
+            type Error = Error;
+            fn try_from(s: &str) -> RawKumaResult<Self> {
+                s.parse()
+            }
+        }
+        impl TryFrom<String> for $slug {
+            type Error = Error;
+            fn try_from(s: String) -> RawKumaResult<Self> {
+                s.parse()
+            }
+        }
+        impl TryFrom<&Url> for $slug {
+            type Error = Error;
+            fn try_from(url: &Url) -> RawKumaResult<Self> {
+                Self::from_url(url)
+            }
+        }
+        impl From<&$slug> for $slug {
+            fn from(slug: &$slug) -> Self {
+                slug.clone()
+            }
+        }
+        impl From<$slug> for String {
+            fn from(slug: $slug) -> Self {
+                slug.0
+            }
+        }
+    };
+}
+
+impl_slug_conversions!(MangaSlug);
+impl_slug_conversions!(ChapterSlug);
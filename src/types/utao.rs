@@ -7,7 +7,8 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "getset")]
 use getset::Getters;
 
-use super::{error::Error, FromElementRef, RawKumaResult};
+use super::{error::Error, ChapterSlug, FromElementRef, MangaSlug, ParseContext, RawKumaResult};
+use crate::parser::{profile::parse_selector, SelectorProfile};
 
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "getset", derive(Getters))]
@@ -21,31 +22,41 @@ pub struct UtaoTitleChapter {
 }
 
 impl<'a> UtaoTitleChapter {
-    pub fn get_a_selector() -> RawKumaResult<Selector> {
-        Ok(Selector::parse(r#"a"#)?)
+    /// Slug of the chapter, read from its url
+    pub fn slug(&self) -> RawKumaResult<ChapterSlug> {
+        ChapterSlug::from_url(&self.url)
     }
-    pub fn get_title_selector(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        data.select(&(Self::get_a_selector()?))
+    pub fn get_a_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.utao.chapter_link)
+    }
+    pub fn get_title_selector(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        data.select(&(Self::get_a_selector(profile)?))
             .next()
-            .ok_or(Error::ElementNotFound("a".to_string()))
+            .ok_or(Error::ElementNotFound(profile.utao.chapter_link.clone()))
     }
 }
 
 impl<'a> FromElementRef<'a> for UtaoTitleChapter {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self> {
-        let title = Self::get_title_selector(data)?;
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.utao.chapter.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self> {
+        let profile = context.get_selector_profile();
+        let title = Self::get_title_selector(data, profile)?;
         RawKumaResult::Ok(
             UtaoTitleChapterBuilder::default()
-                .url(
-                    title
-                        .value()
-                        .attr("href")
-                        .ok_or(Error::AttributeNotFound {
-                            name: "href".to_string(),
-                            element: "a".to_string(),
-                        })?
-                        .parse::<Url>()?,
-                )
+                .url(context.resolve_url(title.value().attr("href").ok_or(
+                    Error::AttributeNotFound {
+                        name: "href".to_string(),
+                        element: profile.utao.chapter_link.clone(),
+                    },
+                )?)?)
                 .text(
                     title
                         .text()
@@ -71,93 +82,110 @@ pub struct UtaoTitleData {
 }
 
 impl<'a> UtaoTitleData {
-    pub fn div_imagu_selector() -> RawKumaResult<Selector> {
-        RawKumaResult::Ok(Selector::parse(r#"div[class="imgu"]"#)?)
+    /// Slug of the series, read from its url
+    pub fn slug(&self) -> RawKumaResult<MangaSlug> {
+        MangaSlug::from_url(&self.url)
+    }
+    pub fn div_imagu_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.utao.image_box)
     }
 
-    pub fn get_imgu_div(html: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
+    pub fn get_imgu_div(
+        html: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
         let divs: ElementRef = html
-            .select(&(Self::div_imagu_selector()?))
+            .select(&(Self::div_imagu_selector(profile)?))
             .next()
-            .ok_or(Error::ElementNotFound("a".to_string()))?;
+            .ok_or(Error::ElementNotFound(profile.utao.image_box.clone()))?;
         RawKumaResult::Ok(divs)
     }
 
-    pub fn get_img_selector() -> RawKumaResult<Selector> {
-        Ok(Selector::parse("img")?)
+    pub fn get_img_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.utao.image)
     }
 
-    pub fn get_image_element(imgu: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        imgu.select(&(Self::get_img_selector()?))
+    pub fn get_image_element(
+        imgu: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        imgu.select(&(Self::get_img_selector(profile)?))
             .next()
-            .ok_or(Error::ElementNotFound("img".to_string()))
+            .ok_or(Error::ElementNotFound(profile.utao.image.clone()))
     }
 
-    pub fn get_a_series_selector() -> RawKumaResult<Selector> {
-        Ok(Selector::parse(r#"a[class="series"]"#)?)
+    pub fn get_a_series_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.utao.series_link)
     }
 
-    pub fn get_a_series_element(imgu: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        imgu.select(&(Self::get_a_series_selector()?))
+    pub fn get_a_series_element(
+        imgu: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        imgu.select(&(Self::get_a_series_selector(profile)?))
             .next()
-            .ok_or(Error::ElementNotFound("a".to_string()))
+            .ok_or(Error::ElementNotFound(profile.utao.series_link.clone()))
     }
 
-    pub fn get_ul_manga_selector() -> RawKumaResult<Selector> {
-        Ok(Selector::parse(r#"ul[class="Manga"]"#)?)
+    pub fn get_ul_manga_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.utao.chapters)
     }
 
-    pub fn get_ul_manga_element(data: &'a ElementRef<'a>) -> RawKumaResult<ElementRef<'a>> {
-        data.select(&(Self::get_ul_manga_selector()?))
+    pub fn get_ul_manga_element(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<ElementRef<'a>> {
+        data.select(&(Self::get_ul_manga_selector(profile)?))
             .next()
-            .ok_or(Error::ElementNotFound(r#"ul[class="Manga"]"#.to_string()))
+            .ok_or(Error::ElementNotFound(profile.utao.chapters.clone()))
     }
 
-    pub fn get_li_selector() -> RawKumaResult<Selector> {
-        Ok(Selector::parse("li")?)
+    pub fn get_li_selector(profile: &SelectorProfile) -> RawKumaResult<Selector> {
+        parse_selector(&profile.utao.chapter)
     }
 
-    pub fn get_chapters_elements(data: &'a ElementRef<'a>) -> RawKumaResult<Vec<ElementRef<'a>>> {
-        Ok(Self::get_ul_manga_element(data)?
-            .select(&(Self::get_li_selector()?))
+    pub fn get_chapters_elements(
+        data: &'a ElementRef<'a>,
+        profile: &SelectorProfile,
+    ) -> RawKumaResult<Vec<ElementRef<'a>>> {
+        Ok(Self::get_ul_manga_element(data, profile)?
+            .select(&(Self::get_li_selector(profile)?))
             .collect())
     }
 }
 
 impl<'a> FromElementRef<'a> for UtaoTitleData {
-    fn from_element_ref(data: &'a ElementRef<'a>) -> RawKumaResult<Self> {
-        let imgu = Self::get_imgu_div(data)?;
-        let image = Self::get_image_element(&imgu)?;
-        let title = Self::get_a_series_element(&imgu)?;
-        let chapters: Vec<ElementRef> = Self::get_chapters_elements(data)?;
+    fn selector(profile: &SelectorProfile) -> String {
+        profile.home.utao.clone()
+    }
+    fn from_element_ref_with(
+        data: &'a ElementRef<'a>,
+        context: &ParseContext,
+    ) -> RawKumaResult<Self> {
+        let profile = context.get_selector_profile();
+        let imgu = Self::get_imgu_div(data, profile)?;
+        let image = Self::get_image_element(&imgu, profile)?;
+        let title = Self::get_a_series_element(&imgu, profile)?;
+        let chapters: Vec<ElementRef> = Self::get_chapters_elements(data, profile)?;
         RawKumaResult::Ok(
             UtaoTitleDataBuilder::default()
-                .chapters(UtaoTitleChapter::from_vec_element(&chapters)?)
-                .image(
-                    format!(
-                        "https:{}",
-                        image.value().attr("src").ok_or(Error::AttributeNotFound {
-                            name: "src".to_string(),
-                            element: "image".to_string()
-                        })?
-                    )
-                    .as_str()
-                    .parse::<Url>()?,
-                )
-                .url(
-                    title
-                        .value()
-                        .attr("href")
-                        .ok_or(Error::AttributeNotFound {
-                            name: "href".to_string(),
-                            element: "a".to_string(),
-                        })?
-                        .parse::<Url>()?,
-                )
+                .chapters(UtaoTitleChapter::from_vec_element(&chapters, context)?)
+                .image(context.resolve_url(image.value().attr("src").ok_or(
+                    Error::AttributeNotFound {
+                        name: "src".to_string(),
+                        element: profile.utao.image.clone(),
+                    },
+                )?)?)
+                .url(context.resolve_url(title.value().attr("href").ok_or(
+                    Error::AttributeNotFound {
+                        name: "href".to_string(),
+                        element: profile.utao.series_link.clone(),
+                    },
+                )?)?)
                 .title(title.value().attr("title").map(|d| d.to_string()).ok_or(
                     Error::AttributeNotFound {
                         name: "title".to_string(),
-                        element: r#"a[class="series"]"#.to_string(),
+                        element: profile.utao.series_link.clone(),
                     },
                 )?)
                 .build()?,
@@ -0,0 +1,2186 @@
+//! Parser and client regression tests against pages in the rawkuma layout.
+//!
+//! The fixtures live in `test/fixtures` and the expected outputs in `test/snapshots`.
+//! The fixtures are still hand-written from the layout of the site, not captures of
+//! it, so the snapshots only check the parsers against that layout and can't catch
+//! a change of the site yet. Replace them with the real pages with
+//! `cargo run --example capture_fixtures`, except `challenge.html` and the
+//! dedicated edge case pages, which are kept by hand, then review the new outputs
+//! with `cargo insta review`.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rawkuma_scraper::{
+    cache::{CacheBackend, CacheEntry, CacheTtlBuilder, MemoryCache, ResponseCache},
+    enums::manga::{Status, Type},
+    parser::{
+        parse_chapter, parse_home, parse_manga_details, parse_manga_list, parse_search,
+        parse_search_with_mode, SelectorProfile,
+    },
+    source::normalize_title,
+    transport::{MemoryTransport, Transport, TransportResponse},
+    types::{
+        error::Error, BixboxData, ChapterDate, ChapterKind, ChapterNumber, ChapterSlug, MangaSlug,
+        ParseContext, ParseMode, ParseWarning, RawKumaResult, TimeUnit,
+    },
+    RawKumaClient, RawKumaClientBuilder, RawKumaClientFromUrl, RetryPolicy, RetryPolicyBuilder,
+    Source, SourceConfig, SourceConfigBuilder, SourceRegistry, Url,
+};
+use reqwest::{
+    header::{HeaderMap, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RETRY_AFTER},
+    StatusCode,
+};
+
+macro_rules! fixture {
+    ($name:literal) => {
+        include_str!(concat!("fixtures/", $name, ".html"))
+    };
+}
+
+macro_rules! assert_snapshot {
+    ($value:expr) => {
+        insta::with_settings!({ sort_maps => true }, {
+            insta::assert_json_snapshot!($value);
+        })
+    };
+}
+
+fn url(url: &str) -> Url {
+    Url::parse(url).unwrap()
+}
+
+#[test]
+fn home() {
+    let data = parse_home(fixture!("home"), &url("https://rawkuma.com/")).unwrap();
+    assert_snapshot!(data);
+}
+
+#[test]
+fn search() {
+    let data = parse_search(
+        fixture!("search"),
+        &url("https://rawkuma.com/page/2/?s=kanojo"),
+    )
+    .unwrap();
+    assert_snapshot!(data);
+}
+
+#[test]
+fn search_empty() {
+    let data = parse_search(
+        fixture!("search_empty"),
+        &url("https://rawkuma.com/?s=nothing-matches-this"),
+    )
+    .unwrap();
+    assert!(data.result.is_empty());
+    assert_snapshot!(data);
+}
+
+#[test]
+fn manga_list() {
+    let data = parse_manga_list(
+        fixture!("manga_list"),
+        &url("https://rawkuma.com/manga/?page=2&order=update"),
+    )
+    .unwrap();
+    assert_snapshot!(data);
+}
+
+#[test]
+fn manga_details() {
+    let data = parse_manga_details(
+        fixture!("manga_details"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    let info = &data.data;
+    assert_eq!(info.author, "rawkuma");
+    assert_eq!(info.writer.as_deref(), Some("MIYAJIMA Reiji"));
+    assert_eq!(info.get_credited_author(), "MIYAJIMA Reiji");
+    assert!(matches!(info.status, Some(Status::Ongoing)));
+    assert!(matches!(info.type_, Some(Type::Manga)));
+    assert_eq!(info.released, Some(2017));
+    assert_eq!(info.followers, Some(1234));
+    assert_eq!(info.views, Some(98765));
+    assert_eq!(
+        info.alternative_titles,
+        ["彼女、お借りします", "Rent-A-Girlfriend"]
+    );
+    assert_snapshot!(data);
+}
+
+#[test]
+fn alternative_titles() {
+    let split = |titles: &str, name: &str| BixboxData::split_alternative_titles(titles, name);
+    assert_eq!(
+        split(
+            "彼女、お借りします, Kanojo, Okarishimasu, Rent-A-Girlfriend",
+            "Kanojo, Okarishimasu"
+        ),
+        [
+            "彼女、お借りします",
+            "Kanojo, Okarishimasu",
+            "Rent-A-Girlfriend"
+        ]
+    );
+    assert_eq!(
+        split("Kanojo, Okarishimasu", "Kanojo,  Okarishimasu"),
+        ["Kanojo, Okarishimasu"]
+    );
+    assert_eq!(
+        split("Hoshi no Oto; 星の音 | Sound of Stars,", "Hoshi no Oto"),
+        ["Hoshi no Oto", "星の音", "Sound of Stars"]
+    );
+    assert!(split("", "Hoshi no Oto").is_empty());
+}
+
+#[test]
+fn oneshot() {
+    let data = parse_manga_details(
+        fixture!("oneshot"),
+        &url("https://rawkuma.com/manga/hoshi-no-oto/"),
+    )
+    .unwrap();
+    assert_eq!(data.chapterlist.chapters.len(), 1);
+    assert_snapshot!(data);
+}
+
+#[test]
+fn chapter() {
+    let data = parse_chapter(
+        fixture!("chapter"),
+        &url("https://rawkuma.com/kanojo-okarishimasu-chapter-321/"),
+    )
+    .unwrap();
+    assert_snapshot!(data);
+}
+
+#[test]
+fn relative_links() {
+    // A mirror serving the same pages with relative links
+    let relative = |html: &str| {
+        html.replace(r#"href="https://rawkuma.com/"#, r#"href="/"#)
+            .replace(r#"src="//rawkuma.com/"#, r#"src="/"#)
+    };
+    let on_mirror = |link: &Url| link.host_str() == Some("mirror.example");
+
+    let home = parse_home(&relative(fixture!("home")), &url("https://mirror.example/")).unwrap();
+    assert!(home
+        .popular_title
+        .iter()
+        .all(|title| on_mirror(&title.url) && on_mirror(&title.image)));
+    assert!(home
+        .latest_update
+        .iter()
+        .all(|title| on_mirror(&title.url) && on_mirror(&title.image)));
+
+    let details = parse_manga_details(
+        &relative(fixture!("manga_details")),
+        &url("https://mirror.example/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    assert_eq!(
+        details.data.image.as_str(),
+        "https://mirror.example/wp-content/uploads/2021/02/kanojo-okarishimasu.jpg"
+    );
+    assert!(details
+        .data
+        .genres
+        .iter()
+        .all(|genre| on_mirror(&genre.url)));
+    assert!(details
+        .chapterlist
+        .chapters
+        .iter()
+        .all(|chapter| on_mirror(&chapter.url)));
+
+    let chapter = parse_chapter(
+        &relative(fixture!("chapter")),
+        &url("https://mirror.example/kanojo-okarishimasu-chapter-321/"),
+    )
+    .unwrap();
+    assert!(chapter
+        .related_mangas
+        .iter()
+        .all(|title| on_mirror(&title.url)));
+}
+
+fn offline_client() -> RawKumaClient {
+    let transport = MemoryTransport::new()
+        .with_html(url("https://rawkuma.com/"), fixture!("home"))
+        .with_html(url("https://rawkuma.com/?s=kanojo"), fixture!("search"))
+        .with_html(
+            url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+            fixture!("manga_details"),
+        )
+        .with_html(
+            url("https://rawkuma.com/kanojo-okarishimasu-chapter-321/"),
+            fixture!("chapter"),
+        );
+    RawKumaClient::with_transport(transport)
+}
+
+#[tokio::test]
+async fn client_offline() {
+    let client = offline_client();
+    let home = client.home().await.unwrap();
+    assert_eq!(home.popular_title.len(), 3);
+    let search = client.search(&"kanojo", 1).await.unwrap();
+    assert_eq!(search.result.len(), 3);
+    let details = client
+        .manga_details("manga/kanojo-okarishimasu/")
+        .await
+        .unwrap();
+    assert_eq!(details.data.name, "Kanojo, Okarishimasu");
+    let chapter = client
+        .chapter("kanojo-okarishimasu-chapter-321/")
+        .await
+        .unwrap();
+    assert_eq!(chapter.title, "Kanojo, Okarishimasu Chapter 321");
+
+    // Slugs are taken as is, by reference or parsed from an url
+    let slug: MangaSlug = "kanojo-okarishimasu".parse().unwrap();
+    let by_ref = client.manga_details(&slug).await.unwrap();
+    let by_url = client
+        .manga_details(&url("https://rawkuma.com/manga/kanojo-okarishimasu/"))
+        .await
+        .unwrap();
+    let by_value = client.manga_details(slug).await.unwrap();
+    assert_eq!(by_ref.data.name, by_value.data.name);
+    assert_eq!(by_url.data.name, by_value.data.name);
+    let slug = ChapterSlug::try_from(&url("https://rawkuma.com/kanojo-okarishimasu-chapter-321/"))
+        .unwrap();
+    assert!(client.chapter(slug).await.is_ok());
+    assert!(matches!(
+        client.manga_details("not a slug").await,
+        Err(Error::InvalidSlug(_))
+    ));
+    assert!(matches!(
+        Source::manga_details(&client, "https://example.org/manga/kanojo-okarishimasu/").await,
+        Err(Error::InvalidSlug(_))
+    ));
+    assert!(Source::chapter(
+        &client,
+        "https://rawkuma.com/kanojo-okarishimasu-chapter-321/"
+    )
+    .await
+    .is_ok());
+}
+
+#[tokio::test]
+async fn client_offline_from_url() {
+    let client = offline_client();
+    let details = RawKumaClientFromUrl::manga_details(
+        &client,
+        url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(details.chapterlist.chapters.len(), 4);
+}
+
+#[tokio::test]
+async fn search_stream_stops_on_visited_page() {
+    use futures::StreamExt;
+
+    // The second page links to itself as the next one
+    let html = fixture!("search").replace("page/3/", "page/2/");
+    let transport = MemoryTransport::new()
+        .with_html(url("https://rawkuma.com/?s=kanojo"), html.clone())
+        .with_html(url("https://rawkuma.com/page/2/?s=kanojo"), html);
+    let client = RawKumaClient::with_transport(transport);
+    let pages: Vec<_> = client.search_stream(&"kanojo").unwrap().collect().await;
+    assert_eq!(pages.len(), 2);
+    assert!(pages.iter().all(Result::is_ok));
+}
+
+#[tokio::test]
+async fn memory_transport_not_found() {
+    let transport = MemoryTransport::new();
+    let res = transport
+        .get(url("https://rawkuma.com/missing/"), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(res.status.as_u16(), 404);
+    assert!(res.error_for_status().is_err());
+}
+
+#[tokio::test]
+async fn memory_transport_directory() {
+    assert_eq!(
+        MemoryTransport::get_fixture_path(&url("https://rawkuma.com/page/2/?s=kanojo")),
+        std::path::Path::new("rawkuma.com/page/2/index@s=kanojo.html")
+    );
+    let directory = std::env::temp_dir().join("rawkuma-scraper-memory-transport");
+    let path = directory.join("rawkuma.com/manga/kanojo-okarishimasu/index.html");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, fixture!("manga_details")).unwrap();
+    let client = RawKumaClient::with_transport(MemoryTransport::from_directory(&directory));
+    let details = client
+        .manga_details("manga/kanojo-okarishimasu/")
+        .await
+        .unwrap();
+    assert_eq!(details.data.name, "Kanojo, Okarishimasu");
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[cfg(feature = "cassette")]
+#[tokio::test]
+async fn cassette_record_replay() {
+    use rawkuma_scraper::transport::{RecordingTransport, ReplayTransport};
+
+    let path = std::env::temp_dir().join("rawkuma-scraper-cassette.json");
+    let _ = std::fs::remove_file(&path);
+    let image = url("https://kumacdn.club/images/k/kanojo-okarishimasu/chapter-321/1.jpg");
+    let transport = MemoryTransport::new()
+        .with_html(url("https://rawkuma.com/"), fixture!("home"))
+        .with_html(image.clone(), vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00]);
+    let recorder = RecordingTransport::to_file(transport, &path);
+    let client = RawKumaClient::with_transport(recorder.clone());
+    let recorded = client.home().await.unwrap();
+    recorder
+        .get(image.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(recorder.cassette().interactions.len(), 2);
+    assert!(!path.exists());
+    recorder.save().unwrap();
+
+    let replay = ReplayTransport::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let client = RawKumaClient::with_transport(replay.clone());
+    let replayed = client.home().await.unwrap();
+    assert_eq!(
+        serde_json::to_value(recorded).unwrap(),
+        serde_json::to_value(replayed).unwrap()
+    );
+    let res = replay.get(image, Default::default()).await.unwrap();
+    assert_eq!(res.body, vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00]);
+    assert!(replay
+        .get(url("https://rawkuma.com/missing/"), Default::default())
+        .await
+        .is_err());
+
+    // A cassette that can't be written fails the explicit save
+    let directory = std::env::temp_dir().join("rawkuma-scraper-missing-directory");
+    let _ = std::fs::remove_dir_all(&directory);
+    let recorder = RecordingTransport::to_file(MemoryTransport::new(), directory.join("c.json"));
+    assert!(matches!(recorder.save(), Err(Error::Io(_))));
+}
+
+/// Answers with the given statuses in order, then serves the home page.
+struct FlakyTransport {
+    statuses: std::sync::Mutex<Vec<u16>>,
+    attempts: std::sync::atomic::AtomicU32,
+}
+
+#[async_trait::async_trait]
+impl Transport for FlakyTransport {
+    async fn get(&self, url: Url, _headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        self.attempts
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let status = self.statuses.lock().unwrap().pop();
+        RawKumaResult::Ok(match status {
+            Some(status) => {
+                let mut headers = HeaderMap::new();
+                headers.insert(RETRY_AFTER, "0".parse().unwrap());
+                TransportResponse::new(url, StatusCode::from_u16(status).unwrap(), "")
+                    .with_headers(headers)
+            }
+            None => TransportResponse::new(url, StatusCode::OK, fixture!("home")),
+        })
+    }
+}
+
+fn flaky_client(statuses: Vec<u16>, max_attempts: u32) -> (RawKumaClient, Arc<FlakyTransport>) {
+    let transport = Arc::new(FlakyTransport {
+        statuses: std::sync::Mutex::new(statuses),
+        attempts: Default::default(),
+    });
+    let client = RawKumaClientBuilder::default()
+        .transport(transport.clone() as Arc<dyn Transport>)
+        .retry_policy(
+            RetryPolicyBuilder::default()
+                .max_attempts(max_attempts)
+                .initial_backoff(Duration::from_millis(1))
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    (client, transport)
+}
+
+#[tokio::test]
+async fn retry_transient_statuses() {
+    let (client, transport) = flaky_client(vec![503, 429], 3);
+    let home = client.home().await.unwrap();
+    assert_eq!(home.popular_title.len(), 3);
+    assert_eq!(
+        transport.attempts.load(std::sync::atomic::Ordering::SeqCst),
+        3
+    );
+}
+
+#[tokio::test]
+async fn retry_gives_up() {
+    let (client, transport) = flaky_client(vec![500, 500, 500], 2);
+    assert!(client.home().await.is_err());
+    assert_eq!(
+        transport.attempts.load(std::sync::atomic::Ordering::SeqCst),
+        2
+    );
+    let (client, transport) = flaky_client(vec![404], 3);
+    assert!(client.home().await.is_err());
+    assert_eq!(
+        transport.attempts.load(std::sync::atomic::Ordering::SeqCst),
+        1
+    );
+}
+
+#[test]
+fn retry_policy() {
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, "120".parse().unwrap());
+    assert_eq!(
+        RetryPolicy::get_retry_after(&headers),
+        Some(Duration::from_secs(120))
+    );
+    let policy = RetryPolicyBuilder::default()
+        .initial_backoff(Duration::from_secs(1))
+        .max_backoff(Duration::from_secs(5))
+        .jitter(false)
+        .build()
+        .unwrap();
+    assert_eq!(policy.get_backoff(1), Duration::from_secs(1));
+    assert_eq!(policy.get_backoff(3), Duration::from_secs(4));
+    assert_eq!(policy.get_backoff(4), Duration::from_secs(5));
+    assert_eq!(policy.get_delay(1, Some(&headers)), Duration::from_secs(5));
+
+    let status = |status: u16| Error::HttpStatus {
+        url: url("https://rawkuma.com/"),
+        status,
+        body_snippet: String::new(),
+    };
+    assert!(status(429).is_retryable());
+    assert!(status(502).is_retryable());
+    assert!(!status(404).is_retryable());
+    assert!(!Error::NoImageSource.is_retryable());
+}
+
+/// The timers don't need a tokio runtime, so the limiter is driven by a plain executor
+#[test]
+fn rate_limit() {
+    use std::time::Instant;
+
+    use rawkuma_scraper::RateLimit;
+
+    let image = url("https://kumacdn.club/images/1.jpg");
+    let transport = MemoryTransport::new()
+        .with_html(url("https://rawkuma.com/"), fixture!("home"))
+        .with_html(image.clone(), vec![0xFF, 0xD8, 0xFF]);
+    let client = RawKumaClientBuilder::default()
+        .transport(Arc::new(transport) as Arc<dyn Transport>)
+        .page_rate_limit(RateLimit::new(20.0, 2))
+        .build()
+        .unwrap();
+
+    futures::executor::block_on(async {
+        // The image CDN has its own, unlimited, budget
+        let start = Instant::now();
+        for _ in 0..4 {
+            RawKumaClientFromUrl::chapter(&client, image.clone())
+                .await
+                .ok();
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // Two requests of burst then one every 50ms, shared by the clones
+        let start = Instant::now();
+        for _ in 0..2 {
+            client.clone().home().await.unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+        client.clone().home().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        client.clone().home().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // The burst is available again once the bucket refilled
+        std::thread::sleep(Duration::from_millis(100));
+        let start = Instant::now();
+        for _ in 0..2 {
+            client.clone().home().await.unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    });
+}
+
+#[tokio::test]
+async fn error_statuses() {
+    let server_error = TransportResponse::new(
+        url("https://rawkuma.com/manga/"),
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "<html>\n  <body>Database   error</body>\n</html>",
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert("cf-mitigated", "challenge".parse().unwrap());
+    let challenge_header =
+        TransportResponse::new(url("https://rawkuma.com/?s=kanojo"), StatusCode::OK, "")
+            .with_headers(headers);
+    let mut html = HeaderMap::new();
+    html.insert(CONTENT_TYPE, "text/html; charset=UTF-8".parse().unwrap());
+    let challenge = TransportResponse::new(
+        url("https://rawkuma.com/"),
+        StatusCode::SERVICE_UNAVAILABLE,
+        fixture!("challenge"),
+    );
+    assert!(!challenge.is_challenge_page());
+    let challenge = challenge.with_headers(html.clone());
+    assert!(challenge.is_challenge_page());
+    // Only the start of the body is searched
+    let late_marker = TransportResponse::new(
+        url("https://rawkuma.com/"),
+        StatusCode::SERVICE_UNAVAILABLE,
+        format!("<html>{}_cf_chl_opt</html>", " ".repeat(16 * 1024)),
+    )
+    .with_headers(html);
+    assert!(!late_marker.is_challenge_page());
+    let transport = MemoryTransport::new()
+        .with_response(url("https://rawkuma.com/"), challenge)
+        .with_response(url("https://rawkuma.com/?s=kanojo"), challenge_header)
+        .with_response(server_error.url.clone(), server_error);
+    let client = RawKumaClient::with_transport(transport).with_retry_policy(RetryPolicy::none());
+
+    assert!(matches!(
+        client.home().await,
+        Err(Error::ChallengePage { .. })
+    ));
+    assert!(matches!(
+        client.search(&"kanojo", 1).await,
+        Err(Error::ChallengePage { .. })
+    ));
+    assert!(matches!(
+        client.manga_details("manga/missing/").await,
+        Err(Error::NotFound { .. })
+    ));
+    match RawKumaClientFromUrl::manga_list(&client, url("https://rawkuma.com/manga/")).await {
+        Err(Error::HttpStatus {
+            status,
+            body_snippet,
+            ..
+        }) => {
+            assert_eq!(status, 500);
+            assert_eq!(body_snippet, "<html> <body>Database error</body> </html>");
+        }
+        _ => panic!("expected an HttpStatus error"),
+    }
+}
+
+/// Serves the home page with an `ETag`, answering `304` to a matching `If-None-Match`.
+#[derive(Default)]
+struct EtagTransport {
+    requests: std::sync::atomic::AtomicU32,
+    not_modified: std::sync::atomic::AtomicU32,
+}
+
+#[async_trait::async_trait]
+impl Transport for EtagTransport {
+    async fn get(&self, url: Url, headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        self.requests
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        if headers
+            .get(IF_NONE_MATCH)
+            .is_some_and(|etag| etag == "\"v1\"")
+        {
+            self.not_modified
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            return RawKumaResult::Ok(TransportResponse::new(url, StatusCode::NOT_MODIFIED, ""));
+        }
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+        RawKumaResult::Ok(
+            TransportResponse::new(url, StatusCode::OK, fixture!("home")).with_headers(headers),
+        )
+    }
+}
+
+#[tokio::test]
+async fn cache_ttl_and_revalidation() {
+    let transport = Arc::new(EtagTransport::default());
+    let cached_client = |ttl: Duration| {
+        RawKumaClientBuilder::default()
+            .transport(transport.clone() as Arc<dyn Transport>)
+            .cache(
+                ResponseCache::memory(8)
+                    .with_ttl(CacheTtlBuilder::default().home(ttl).build().unwrap()),
+            )
+            .build()
+            .unwrap()
+    };
+    let requests = || transport.requests.load(std::sync::atomic::Ordering::SeqCst);
+
+    let client = cached_client(Duration::from_secs(60));
+    client.home().await.unwrap();
+    client.home().await.unwrap();
+    assert_eq!(requests(), 1);
+
+    let client = cached_client(Duration::ZERO);
+    client.home().await.unwrap();
+    let home = client.home().await.unwrap();
+    assert_eq!(home.popular_title.len(), 3);
+    assert_eq!(requests(), 3);
+    assert_eq!(
+        transport
+            .not_modified
+            .load(std::sync::atomic::Ordering::SeqCst),
+        1
+    );
+}
+
+#[tokio::test]
+async fn memory_cache_lru() {
+    let cache = MemoryCache::new(2);
+    let entry = |path: &str| {
+        CacheEntry::new(&TransportResponse::new(
+            url(path),
+            StatusCode::OK,
+            path.to_string(),
+        ))
+    };
+    let (a, b, c) = (
+        url("https://rawkuma.com/a/"),
+        url("https://rawkuma.com/b/"),
+        url("https://rawkuma.com/c/"),
+    );
+    cache.put(&a, entry(a.as_str())).await.unwrap();
+    cache.put(&b, entry(b.as_str())).await.unwrap();
+    assert!(cache.get(&a).await.is_some());
+    cache.put(&c, entry(c.as_str())).await.unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(&a).await.is_some());
+    assert!(cache.get(&b).await.is_none());
+    assert!(cache.get(&c).await.is_some());
+}
+
+#[tokio::test]
+async fn disk_cache() {
+    use rawkuma_scraper::cache::DiskCache;
+
+    let directory = std::env::temp_dir().join("rawkuma-scraper-disk-cache");
+    let _ = std::fs::remove_dir_all(&directory);
+    let transport = Arc::new(EtagTransport::default());
+    for _ in 0..2 {
+        let client = RawKumaClientBuilder::default()
+            .transport(transport.clone() as Arc<dyn Transport>)
+            .cache(ResponseCache::disk(&directory))
+            .build()
+            .unwrap();
+        client.home().await.unwrap();
+    }
+    assert_eq!(
+        transport.requests.load(std::sync::atomic::Ordering::SeqCst),
+        1
+    );
+    let cache = DiskCache::new(&directory);
+    let home = url("https://rawkuma.com/");
+    assert!(cache.get_entry_path(&home).is_file());
+    cache.remove(&home).await.unwrap();
+    assert!(cache.get(&home).await.is_none());
+    cache.remove(&home).await.unwrap();
+
+    // Urls differing only by their query characters are kept apart
+    let entry = |body: &str| {
+        CacheEntry::new(&TransportResponse::new(
+            url("https://rawkuma.com/"),
+            StatusCode::OK,
+            body,
+        ))
+    };
+    let pairs = [
+        (
+            "https://rawkuma.com/manga/?page=1&order=a",
+            "https://rawkuma.com/manga/?page=1_order=a",
+        ),
+        ("https://rawkuma.com/?s=a%20b", "https://rawkuma.com/?s=a_b"),
+    ];
+    for (first, second) in pairs {
+        let (first, second) = (url(first), url(second));
+        assert_ne!(cache.get_entry_path(&first), cache.get_entry_path(&second));
+        cache.put(&first, entry("first")).await.unwrap();
+        cache.put(&second, entry("second")).await.unwrap();
+        let cached = cache.get(&first).await.unwrap();
+        assert_eq!(cached.response.body.to_bytes().unwrap(), b"first");
+    }
+
+    // A file holding another url is a miss
+    let other = url("https://rawkuma.com/?s=other");
+    std::fs::copy(
+        cache.get_entry_path(&url("https://rawkuma.com/?s=a_b")),
+        cache.get_entry_path(&other),
+    )
+    .unwrap();
+    assert!(cache.get(&other).await.is_none());
+
+    // Concurrent writers of the same page each have their own partial file
+    std::thread::scope(|scope| {
+        for index in 0..32 {
+            let (cache, home, entry) = (&cache, &home, &entry);
+            scope.spawn(move || {
+                futures::executor::block_on(cache.put(home, entry(&index.to_string()))).unwrap()
+            });
+        }
+    });
+    assert!(cache.get(&home).await.is_some());
+    let files: Vec<_> = std::fs::read_dir(cache.get_entry_path(&home).parent().unwrap())
+        .unwrap()
+        .map(|file| file.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert!(files.iter().all(|file| file.ends_with(".json")));
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    // A page that cannot be written is still served, the error is reported
+    std::fs::write(&directory, "not a directory").unwrap();
+    let errors = Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let reported = errors.clone();
+    let client = RawKumaClientBuilder::default()
+        .transport(transport.clone() as Arc<dyn Transport>)
+        .cache(
+            ResponseCache::disk(&directory).with_error_handler(Arc::new(move |_| {
+                reported.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(client.home().await.unwrap().popular_title.len(), 3);
+    assert_eq!(errors.load(std::sync::atomic::Ordering::SeqCst), 1);
+    std::fs::remove_file(directory).unwrap();
+}
+
+#[tokio::test]
+async fn shared_client() {
+    let client = Arc::new(offline_client());
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.home().await.map(|home| home.popular_title.len()) })
+        })
+        .collect();
+    for task in tasks {
+        assert_eq!(task.await.unwrap().unwrap(), 3);
+    }
+}
+
+#[test]
+fn client_and_error_are_thread_safe() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<RawKumaClient>();
+    assert_send_sync::<Error>();
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn blocking_client() {
+    let client = rawkuma_scraper::RawKumaBlockingClient::from_client(offline_client()).unwrap();
+    assert_eq!(client.home().unwrap().popular_title.len(), 3);
+    assert_eq!(client.search(&"kanojo", 1).unwrap().result.len(), 3);
+    assert_eq!(
+        client
+            .chapter("kanojo-okarishimasu-chapter-321/")
+            .unwrap()
+            .title,
+        "Kanojo, Okarishimasu Chapter 321"
+    );
+}
+
+#[test]
+fn slugs() {
+    let manga: MangaSlug = "kanojo-okarishimasu".parse().unwrap();
+    for input in [
+        "/kanojo-okarishimasu/",
+        "manga/kanojo-okarishimasu/",
+        "/manga/kanojo-okarishimasu",
+        "https://rawkuma.com/manga/kanojo-okarishimasu/",
+    ] {
+        assert_eq!(input.parse::<MangaSlug>().unwrap(), manga);
+    }
+    assert_eq!(
+        manga.to_url(&url("https://rawkuma.com/")).unwrap().as_str(),
+        "https://rawkuma.com/manga/kanojo-okarishimasu/"
+    );
+    assert!("".parse::<MangaSlug>().is_err());
+    assert!("https://rawkuma.com/kanojo-okarishimasu-chapter-321/"
+        .parse::<MangaSlug>()
+        .is_err());
+
+    let chapter: ChapterSlug = "https://rawkuma.com/kanojo-okarishimasu-chapter-321/"
+        .parse()
+        .unwrap();
+    assert_eq!(chapter.as_str(), "kanojo-okarishimasu-chapter-321");
+    assert_eq!(chapter, "/kanojo-okarishimasu-chapter-321".parse().unwrap());
+    assert!("a/b".parse::<ChapterSlug>().is_err());
+
+    // The other pages of the theme are not chapters
+    for input in [
+        "https://rawkuma.com/manga/",
+        "https://rawkuma.com/genres/",
+        "/page/",
+        "manga",
+    ] {
+        assert!(input.parse::<ChapterSlug>().is_err(), "{}", input);
+    }
+    assert!("https://rawkuma.com/manga/page/"
+        .parse::<MangaSlug>()
+        .is_err());
+
+    // With the site known, the urls of other hosts are rejected
+    let api_url = url("https://rawkuma.com/");
+    let chapter_url = url("https://rawkuma.com/kanojo-okarishimasu-chapter-321/");
+    assert_eq!(
+        ChapterSlug::from_source_url(&chapter_url, &api_url).unwrap(),
+        chapter
+    );
+    assert!(ChapterSlug::from_source_url(
+        &url("https://example.org/kanojo-okarishimasu-chapter-321/"),
+        &api_url
+    )
+    .is_err());
+    assert!(
+        MangaSlug::parse_on("https://example.org/manga/kanojo-okarishimasu/", &api_url).is_err()
+    );
+    assert_eq!(
+        MangaSlug::parse_on("kanojo-okarishimasu", &api_url).unwrap(),
+        manga
+    );
+    // A site under a sub-path
+    assert_eq!(
+        MangaSlug::from_source_url(
+            &url("https://example.org/raw/manga/kanojo-okarishimasu/"),
+            &url("https://example.org/raw/")
+        )
+        .unwrap(),
+        manga
+    );
+    assert_eq!(
+        serde_json::to_string(&chapter).unwrap(),
+        "\"kanojo-okarishimasu-chapter-321\""
+    );
+}
+
+#[test]
+fn slug_accessors() {
+    let details = parse_manga_details(
+        fixture!("manga_details"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    assert_eq!(
+        details.chapterlist.chapters[1].slug().unwrap().as_str(),
+        "kanojo-okarishimasu-chapter-320-5"
+    );
+    assert_eq!(
+        details.related_series[0].slug().unwrap().as_str(),
+        "kanojo-mo-kanojo"
+    );
+    let home = parse_home(fixture!("home"), &url("https://rawkuma.com/")).unwrap();
+    assert_eq!(
+        home.latest_update[0].slug().unwrap().as_str(),
+        "kanojo-okarishimasu"
+    );
+    assert_eq!(
+        home.latest_update[0].chapters[0].slug().unwrap().as_str(),
+        "kanojo-okarishimasu-chapter-321"
+    );
+    let chapter = parse_chapter(
+        fixture!("chapter"),
+        &url("https://rawkuma.com/kanojo-okarishimasu-chapter-321/"),
+    )
+    .unwrap();
+    assert_eq!(
+        chapter.sources.prev_slug().unwrap().unwrap().as_str(),
+        "kanojo-okarishimasu-chapter-320-5"
+    );
+    assert!(chapter.sources.next_slug().unwrap().is_none());
+}
+
+#[test]
+fn chapter_dates() {
+    let reference = Utc.with_ymd_and_hms(2024, 3, 3, 12, 30, 0).unwrap();
+    assert_eq!(
+        ChapterDate::parse("March 3, 2024"),
+        Some(ChapterDate::Absolute(
+            NaiveDate::from_ymd_opt(2024, 3, 3).unwrap()
+        ))
+    );
+    let relative = ChapterDate::parse("2 hours ago").unwrap();
+    assert_eq!(
+        relative,
+        ChapterDate::Relative {
+            amount: 2,
+            unit: TimeUnit::Hour
+        }
+    );
+    assert_eq!(
+        relative.resolve(&reference),
+        Some(Utc.with_ymd_and_hms(2024, 3, 3, 10, 30, 0).unwrap())
+    );
+    assert_eq!(
+        ChapterDate::parse("a day ago")
+            .unwrap()
+            .to_naive_date(&reference),
+        NaiveDate::from_ymd_opt(2024, 3, 2)
+    );
+    assert_eq!(
+        ChapterDate::parse("1 month ago")
+            .unwrap()
+            .to_naive_date(&reference),
+        NaiveDate::from_ymd_opt(2024, 2, 3)
+    );
+    assert_eq!(ChapterDate::parse("someday"), None);
+
+    let details = parse_manga_details(
+        fixture!("relative_dates"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    let times: Vec<_> = details
+        .chapterlist
+        .chapters
+        .iter()
+        .map(|chapter| chapter.get_release_time(&reference).unwrap())
+        .collect();
+    assert_eq!(
+        times,
+        [
+            Utc.with_ymd_and_hms(2024, 3, 3, 10, 30, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 2, 12, 30, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 2, 18, 12, 30, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 2, 10, 0, 0, 0).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn chapter_numbers() {
+    let number = ChapterNumber::parse("Chapter 21.1");
+    assert_eq!((number.main, number.sub.as_deref()), (Some(21), Some("1")));
+    let number = ChapterNumber::parse("Vol.3 Ch.21");
+    assert_eq!((number.volume, number.main), (Some(3), Some(21)));
+    let number = ChapterNumber::parse("第3巻 第21話");
+    assert_eq!((number.volume, number.main), (Some(3), Some(21)));
+    let number = ChapterNumber::parse("Chapter 100 [END]");
+    assert_eq!(number.main, Some(100));
+    assert!(number.is_end);
+    assert_eq!(number.to_string(), "Chapter 100 [END]");
+    assert_eq!(
+        ChapterNumber::parse("Side Story 2").kind,
+        ChapterKind::SideStory
+    );
+    let oneshot = ChapterNumber::parse_with_fallback("Oneshot", "1");
+    assert_eq!((oneshot.kind, oneshot.main), (ChapterKind::Oneshot, None));
+    assert_eq!(
+        ChapterNumber::parse_with_fallback("Extra", "50.5").to_string(),
+        "Chapter 50.5 Extra"
+    );
+
+    let mut labels = vec![
+        "Chapter 21.10",
+        "Chapter 21 Extra",
+        "Chapter 2",
+        "Chapter 21.2",
+        "Chapter 21",
+        "Chapter 10",
+    ];
+    labels.sort_by_key(|label| ChapterNumber::parse(label));
+    assert_eq!(
+        labels,
+        vec![
+            "Chapter 2",
+            "Chapter 10",
+            "Chapter 21",
+            "Chapter 21 Extra",
+            "Chapter 21.10",
+            "Chapter 21.2"
+        ]
+    );
+
+    // The fraction is a decimal
+    let parse = ChapterNumber::parse;
+    assert_ne!(parse("Chapter 21.05"), parse("Chapter 21.5"));
+    assert!(parse("Chapter 21.05") < parse("Chapter 21.5"));
+    assert!(parse("Chapter 20.25") < parse("Chapter 20.5"));
+    assert_eq!(parse("Chapter 21.50"), parse("Chapter 21.5"));
+    assert_eq!(parse("Chapter 21.50").to_string(), "Chapter 21.5");
+    assert_eq!(parse("Chapter 21.0"), parse("Chapter 21"));
+
+    // The title of the chapter does not set the kind or the end marker
+    let number = parse("Chapter 12 - The End of Summer");
+    assert!(!number.is_end);
+    assert_eq!(number.kind, ChapterKind::Regular);
+    assert_eq!(
+        parse("Chapter 13: A Special Bonus for Everyone").kind,
+        ChapterKind::Regular
+    );
+    assert!(parse("Chapter 40 Final").is_end);
+    assert!(parse("第100話 完").is_end);
+    assert_eq!(parse("Extra Chapter 5").kind, ChapterKind::Extra);
+
+    // The number after a chapter marker wins
+    let number = parse("Season 2 Chapter 5");
+    assert_eq!(number.main, Some(5));
+    assert_eq!(parse("Part 3 Ch. 12.5").to_string(), "Chapter 12.5");
+
+    let mut details = parse_manga_details(
+        fixture!("manga_details"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    let duplicate = details.chapterlist.chapters[0].clone();
+    details.chapterlist.chapters.push(duplicate);
+    // 320.05 is another chapter than 320.5
+    let mut close = details.chapterlist.chapters[0].clone();
+    close.chapter_num = "Chapter 320.05".to_string();
+    close.number = ChapterNumber::parse(&close.chapter_num);
+    details.chapterlist.chapters.push(close);
+    details.chapterlist.dedup_by_number();
+    let numbers: Vec<String> = details
+        .chapterlist
+        .chapters
+        .iter()
+        .map(|chapter| chapter.number.to_string())
+        .collect();
+    assert_eq!(
+        numbers,
+        vec![
+            "Chapter 1",
+            "Chapter 320",
+            "Chapter 320.05",
+            "Chapter 320.5",
+            "Chapter 321"
+        ]
+    );
+
+    // The end marker does not make a second chapter
+    let mut end = details.chapterlist.chapters[4].clone();
+    end.chapter_num = "Chapter 321 [END]".to_string();
+    end.number = ChapterNumber::parse(&end.chapter_num);
+    assert_eq!(end.number, details.chapterlist.chapters[4].number);
+    details.chapterlist.chapters.insert(0, end);
+    details.chapterlist.dedup_by_number();
+    let labels: Vec<&str> = details
+        .chapterlist
+        .chapters
+        .iter()
+        .map(|chapter| chapter.chapter_num.as_str())
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            "Chapter 1",
+            "Chapter 320",
+            "Chapter 320.05",
+            "Chapter 320.5",
+            "Chapter 321 [END]"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn lenient_parsing() {
+    // The rating of the second card is removed
+    let html = fixture!("search").replacen(r#"<div class="numscore">7.1</div>"#, "", 1);
+    let page_url = url("https://rawkuma.com/?s=kanojo");
+
+    let strict = parse_search_with_mode(&html, &page_url, ParseMode::Strict);
+    assert!(matches!(strict, Err(Error::ElementNotFound(_))));
+
+    let parsed = parse_search_with_mode(&html, &page_url, ParseMode::Lenient).unwrap();
+    assert_eq!(parsed.data.result.len(), 2);
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].selector, r#"div[class="bsx"]"#);
+    assert!(parsed.warnings[0].element.contains("Kanojo mo Kanojo"));
+    assert!(matches!(
+        parsed.warnings[0].error,
+        Error::ElementNotFound(_)
+    ));
+
+    // By default, the broken card is skipped as before the parse modes
+    assert_eq!(ParseMode::default(), ParseMode::Lenient);
+    let client = RawKumaClient::with_transport(
+        MemoryTransport::new().with_html(page_url.clone(), html.clone()),
+    );
+    assert_eq!(client.search(&"kanojo", 1).await.unwrap().result.len(), 2);
+
+    let warnings: Arc<std::sync::Mutex<Vec<String>>> = Default::default();
+    let handler_warnings = warnings.clone();
+    let client = RawKumaClient::with_transport(MemoryTransport::new().with_html(page_url, html))
+        .with_parse_mode(ParseMode::Lenient)
+        .with_parse_warning_handler(Arc::new(move |warning: &ParseWarning| {
+            handler_warnings.lock().unwrap().push(warning.to_string());
+        }));
+    let search = client.search(&"kanojo", 1).await.unwrap();
+    assert_eq!(search.result.len(), 2);
+    assert_eq!(warnings.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn selector_profile() {
+    // The site renamed the class of the ratings
+    let html = fixture!("search").replace("numscore", "score");
+    let page_url = url("https://rawkuma.com/?s=kanojo");
+    assert!(parse_search_with_mode(&html, &page_url, ParseMode::Strict).is_err());
+    // Every card is broken, the default mode skips them all
+    assert!(parse_search(&html, &page_url).unwrap().result.is_empty());
+
+    let profile = SelectorProfile::from_json(r#"{ "bsx": { "rating": "div.score" } }"#).unwrap();
+    assert_eq!(profile.bsx.card, SelectorProfile::rawkuma().bsx.card);
+    let context =
+        ParseContext::new(ParseMode::Strict).with_selector_profile(Arc::new(profile.clone()));
+    let search = parse_search_with_mode(&html, &page_url, context).unwrap();
+    assert_eq!(search.data.result.len(), 3);
+    assert!(parse_search_with_mode(&html, &page_url, ParseMode::Strict).is_err());
+
+    let client = RawKumaClient::with_transport(MemoryTransport::new().with_html(page_url, html))
+        .with_selector_profile(profile);
+    assert_eq!(client.search(&"kanojo", 1).await.unwrap().result.len(), 3);
+
+    let invalid = SelectorProfile::from_json(r#"{ "chapter_list": { "download": "a..dload" } }"#);
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn selector_profile_errors_and_labels() {
+    use rawkuma_scraper::parser::{
+        parse_home_with_mode, parse_manga_details_with_mode, parse_search_with_mode,
+    };
+
+    let page_url = url("https://rawkuma.com/manga/kanojo-okarishimasu/");
+    let strict = |profile: SelectorProfile| {
+        ParseContext::new(ParseMode::Strict).with_selector_profile(Arc::new(profile))
+    };
+
+    // The errors name the selectors of the profile
+    let profile = SelectorProfile::from_json(
+        r#"{ "chapter_list": { "eph_num": "div.num", "link": "a.go", "date": "span.when" } }"#,
+    )
+    .unwrap();
+    let html = fixture!("manga_details")
+        .replace(r#"class="eph-num""#, r#"class="num""#)
+        .replace(
+            "<a href=\"https://rawkuma.com/kanojo",
+            "<a class=\"go\" href=\"https://rawkuma.com/kanojo",
+        );
+    let error = parse_manga_details_with_mode(&html, &page_url, strict(profile))
+        .err()
+        .unwrap();
+    assert!(
+        matches!(
+            &error,
+            Error::ElementNotFoundInNested { element, parent }
+                if element == "span.when" && parent == "div.num a.go"
+        ),
+        "{}",
+        error
+    );
+
+    let search_url = url("https://rawkuma.com/page/2/?s=kanojo");
+    let html = fixture!("search").replace("<img src=", "<img data-src=");
+    let error = parse_search_with_mode(&html, &search_url, strict(SelectorProfile::rawkuma()))
+        .err()
+        .unwrap();
+    assert!(
+        matches!(&error, Error::AttributeNotFound { name, element } if name == "src" && element == "img"),
+        "{}",
+        error
+    );
+    let html = fixture!("search").replace(
+        r#"class="next page-numbers" href="#,
+        r#"class="next page-numbers" data-href="#,
+    );
+    let error = parse_search_with_mode(&html, &search_url, strict(SelectorProfile::rawkuma()))
+        .err()
+        .unwrap();
+    assert!(
+        matches!(
+            &error,
+            Error::AttributeNotFound { element, .. } if element == "div.pagination .page-numbers"
+        ),
+        "{}",
+        error
+    );
+    let profile =
+        SelectorProfile::from_json(r#"{ "utao": { "image_box": "div.cover" } }"#).unwrap();
+    let error = parse_home_with_mode(
+        fixture!("home"),
+        &url("https://rawkuma.com/"),
+        strict(profile),
+    )
+    .err()
+    .unwrap();
+    assert!(
+        matches!(&error, Error::ElementNotFound(element) if element == "div.cover"),
+        "{}",
+        error
+    );
+
+    // The popular titles are read from the block of the profile
+    let html = fixture!("home").replace("bixbox hothome full", "bixbox trending");
+    let home_url = url("https://rawkuma.com/");
+    let error = parse_home_with_mode(&html, &home_url, strict(SelectorProfile::rawkuma()))
+        .err()
+        .unwrap();
+    assert!(
+        matches!(&error, Error::ElementNotFound(element) if element == "div.bixbox.hothome"),
+        "{}",
+        error
+    );
+    let profile =
+        SelectorProfile::from_json(r#"{ "home": { "hothome": "div.trending" } }"#).unwrap();
+    let home = parse_home_with_mode(&html, &home_url, strict(profile)).unwrap();
+    assert_eq!(home.data.popular_title.len(), 3);
+
+    // The labels of the info table can be renamed, they are not validated as selectors
+    let html = fixture!("manga_details")
+        .replace("<b>Released</b>", "<b>Year</b>")
+        .replace("Status <i>", "Estado: <i>");
+    let default = parse_manga_details(&html, &page_url).unwrap();
+    assert_eq!(default.data.released, None);
+    assert!(default.data.status.is_none());
+    let profile =
+        SelectorProfile::from_json(r#"{ "labels": { "released": "Year", "status": "Estado:" } }"#)
+            .unwrap();
+    assert_eq!(profile.labels.author, "Author");
+    let details = parse_manga_details_with_mode(&html, &page_url, strict(profile))
+        .unwrap()
+        .data
+        .data;
+    assert_eq!(details.released, Some(2017));
+    assert!(matches!(details.status, Some(Status::Ongoing)));
+}
+
+#[tokio::test]
+async fn selector_profile_per_client() {
+    // Each client parses with the profile of its own source, even when they run together
+    let html = fixture!("search").replace("numscore", "score");
+    let page_url = url("https://rawkuma.com/?s=kanojo");
+    let profile = SelectorProfile::from_json(r#"{ "bsx": { "rating": "div.score" } }"#).unwrap();
+    let rawkuma = RawKumaClient::with_transport(
+        MemoryTransport::new().with_html(page_url.clone(), html.clone()),
+    );
+    let fixed = RawKumaClient::with_transport(MemoryTransport::new().with_html(page_url, html))
+        .with_selector_profile(profile);
+    let (default_search, fixed_search) =
+        tokio::join!(rawkuma.search(&"kanojo", 1), fixed.search(&"kanojo", 1));
+    // The default profile skips every card
+    assert!(default_search.unwrap().result.is_empty());
+    assert_eq!(fixed_search.unwrap().result.len(), 3);
+    assert!(rawkuma
+        .search(&"kanojo", 1)
+        .await
+        .unwrap()
+        .result
+        .is_empty());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn selector_profile_toml() {
+    let profile = SelectorProfile::from_toml(
+        "content = \"main\"\n[chapter_list]\ndownload = \"a.download\"\n",
+    )
+    .unwrap();
+    assert_eq!(profile.content, "main");
+    assert_eq!(profile.chapter_list.download, "a.download");
+    assert_eq!(
+        profile.chapter_list.date,
+        SelectorProfile::rawkuma().chapter_list.date
+    );
+}
+
+#[tokio::test]
+async fn sibling_source() {
+    // A site on the same theme, with another chapter class and date format
+    let html = fixture!("manga_details")
+        .replace("eph-num", "chapter-num")
+        .replace("February 28, 2024", "28.02.2024");
+    let mut profile = SelectorProfile::rawkuma();
+    profile.chapter_list.eph_num = "div.chapter-num".to_string();
+    let config = SourceConfigBuilder::default()
+        .id("example")
+        .name("Example Raw")
+        .base_url(url("https://raw.example.org/"))
+        .language("ja")
+        .date_format("%d.%m.%Y")
+        .selector_profile(Arc::new(profile))
+        .build()
+        .unwrap();
+    let transport = MemoryTransport::new().with_html(
+        url("https://raw.example.org/manga/kanojo-okarishimasu/"),
+        html,
+    );
+    let source: Box<dyn Source> =
+        Box::new(RawKumaClient::with_transport(transport).with_source(config.clone()));
+    assert_eq!(source.id(), "example");
+    assert_eq!(source.base_url().as_str(), "https://raw.example.org/");
+
+    let details = source.manga_details("kanojo-okarishimasu").await.unwrap();
+    assert_eq!(details.chapterlist.chapters.len(), 4);
+    let date = details
+        .chapterlist
+        .chapters
+        .iter()
+        .find(|chapter| chapter.chapter_date == "28.02.2024")
+        .and_then(|chapter| chapter.date);
+    assert_eq!(
+        date,
+        Some(ChapterDate::Absolute(
+            NaiveDate::from_ymd_opt(2024, 2, 28).unwrap()
+        ))
+    );
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(SourceConfig::from_json(&json).unwrap(), config);
+    assert_eq!(RawKumaClient::default().config(), &SourceConfig::rawkuma());
+}
+
+fn sibling_config(id: &str, base_url: &str) -> SourceConfig {
+    SourceConfigBuilder::default()
+        .id(id)
+        .name(id)
+        .base_url(url(base_url))
+        .language("ja")
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn source_registry() {
+    assert_eq!(
+        normalize_title("  Kanojo, OKARISHIMASU!"),
+        "kanojo okarishimasu"
+    );
+
+    // The site lists a series twice
+    let search = fixture!("search");
+    let card = search
+        .lines()
+        .find(|line| line.contains("kanojo-okarishimasu/"))
+        .unwrap();
+    let rawkuma = MemoryTransport::new().with_html(
+        url("https://rawkuma.com/?s=kanojo"),
+        search
+            .replacen(card, &format!("{}\n{}", card, card), 1)
+            .replace("Kanojo mo Kanojo", "???"),
+    );
+    // Same series spelled differently, and one found only on this site,
+    // its title without any letter not matching the one of the other site
+    let example = MemoryTransport::new().with_html(
+        url("https://raw.example.org/?s=kanojo"),
+        fixture!("search")
+            .replace("Kanojo no Tomodachi", "KANOJO NO TOMODACHI!")
+            .replace("Kanojo mo Kanojo", "!!!"),
+    );
+    let mut registry = SourceRegistry::new()
+        .with_source(RawKumaClient::with_transport(rawkuma))
+        .unwrap()
+        .with_source(
+            RawKumaClient::with_transport(example)
+                .with_source(sibling_config("example", "https://raw.example.org/")),
+        )
+        .unwrap();
+    // Its page is missing
+    registry
+        .register(
+            RawKumaClient::with_transport(MemoryTransport::new())
+                .with_source(sibling_config("broken", "https://raw.broken.org/")),
+        )
+        .unwrap();
+    assert!(matches!(
+        registry.register(RawKumaClient::default()),
+        Err(Error::DuplicateSource(id)) if id == "rawkuma"
+    ));
+    assert_eq!(registry.sources().len(), 3);
+    assert_eq!(registry.get("example").unwrap().name(), "example");
+
+    let search = registry.search("kanojo", 1).await;
+    assert_eq!(search.failures.len(), 1);
+    assert_eq!(search.failures[0].source, "broken");
+    let titles = search
+        .titles
+        .iter()
+        .map(|title| {
+            let sources = title
+                .results
+                .iter()
+                .map(|result| result.source.as_str())
+                .collect::<Vec<_>>();
+            (title.title.as_str(), sources)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        vec![
+            ("Kanojo, Okarishimasu", vec!["rawkuma", "example"]),
+            ("???", vec!["rawkuma"]),
+            ("Kanojo no Tomodachi", vec!["rawkuma", "example"]),
+            ("!!!", vec!["example"]),
+        ]
+    );
+    assert_eq!(search.titles[1].normalized_title, "???");
+
+    assert!(registry.remove("broken").is_some());
+    assert!(registry.search("kanojo", 1).await.failures.is_empty());
+}
+
+/// Never answers
+struct PendingTransport;
+
+#[async_trait::async_trait]
+impl Transport for PendingTransport {
+    async fn get(&self, _url: Url, _headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        futures::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn source_registry_timeout() {
+    let timeout = Duration::from_millis(50);
+    let registry = SourceRegistry::new()
+        .with_timeout(timeout)
+        .with_source(RawKumaClient::with_transport(
+            MemoryTransport::new().with_html(
+                url("https://rawkuma.com/?s=kanojo"),
+                fixture!("search").to_string(),
+            ),
+        ))
+        .unwrap()
+        .with_source(
+            RawKumaClient::with_transport(PendingTransport)
+                .with_source(sibling_config("pending", "https://raw.pending.org/")),
+        )
+        .unwrap();
+    assert_eq!(registry.get_timeout(), timeout);
+
+    let search = registry.search("kanojo", 1).await;
+    assert_eq!(search.titles.len(), 3);
+    assert_eq!(search.failures.len(), 1);
+    assert!(matches!(
+        &search.failures[0].error,
+        Error::SourceTimeout(id, elapsed) if id == "pending" && *elapsed == timeout
+    ));
+}
+
+#[cfg(feature = "download")]
+#[test]
+fn image_formats() {
+    use rawkuma_scraper::download::ImageFormat;
+
+    let signatures: [(&[u8], ImageFormat); 7] = [
+        (b"\xFF\xD8\xFF\xE0\x00\x10JFIF", ImageFormat::Jpeg),
+        (b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR", ImageFormat::Png),
+        (b"GIF87a\x01\x00", ImageFormat::Gif),
+        (b"GIF89a\x01\x00", ImageFormat::Gif),
+        (b"RIFF\x24\x00\x00\x00WEBPVP8 ", ImageFormat::Webp),
+        (b"\x00\x00\x00\x1cftypavif\x00\x00", ImageFormat::Avif),
+        (b"BM\x36\x00\x0c\x00", ImageFormat::Bmp),
+    ];
+    for (data, format) in signatures {
+        assert_eq!(ImageFormat::from_magic_bytes(data), Some(format));
+    }
+    assert_eq!(ImageFormat::from_magic_bytes(b""), None);
+    assert_eq!(ImageFormat::from_magic_bytes(b"<html>"), None);
+    assert_eq!(
+        ImageFormat::from_magic_bytes(b"RIFF\x24\x00\x00\x00WAVE"),
+        None
+    );
+
+    assert_eq!(
+        ImageFormat::from_mime_type("image/JPG; charset=binary"),
+        Some(ImageFormat::Jpeg)
+    );
+    assert_eq!(ImageFormat::from_mime_type("text/html"), None);
+    assert_eq!(ImageFormat::from_extension("JPEG"), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_extension("txt"), None);
+
+    // The bytes win over the header, which wins over the extension
+    let page = url("https://img.example.org/chapter/001.webp");
+    assert_eq!(
+        ImageFormat::detect(b"\x89PNG\r\n\x1a\n", Some("image/jpeg"), &page),
+        Some(ImageFormat::Png)
+    );
+    assert_eq!(
+        ImageFormat::detect(b"????", Some("image/gif"), &page),
+        Some(ImageFormat::Gif)
+    );
+    assert_eq!(
+        ImageFormat::detect(b"????", Some("application/octet-stream"), &page),
+        Some(ImageFormat::Webp)
+    );
+    assert_eq!(
+        ImageFormat::detect(b"????", None, &url("https://img.example.org/page")),
+        None
+    );
+}
+
+#[cfg(feature = "download")]
+#[test]
+fn page_file_names() {
+    use rawkuma_scraper::download::{ChapterDownloader, ChapterPage, ImageFormat};
+
+    let page = |index: usize, format: ImageFormat| ChapterPage {
+        index,
+        url: url("https://img.example.org/page.jpg"),
+        format,
+        data: Vec::new(),
+    };
+    assert_eq!(ChapterDownloader::get_file_name_width(9), 3);
+    assert_eq!(ChapterDownloader::get_file_name_width(1000), 4);
+    assert_eq!(page(0, ImageFormat::Jpeg).file_name(3), "001.jpg");
+    assert_eq!(page(41, ImageFormat::Png).file_name(3), "042.png");
+    assert_eq!(page(999, ImageFormat::Webp).file_name(4), "1000.webp");
+}
+
+#[cfg(any(feature = "cbz", feature = "epub", feature = "pdf"))]
+const CHAPTER_IMAGES: [&str; 3] = [
+    "https://kumacdn.club/images/k/kanojo-okarishimasu/chapter-321/1-64e1d5a7.jpg",
+    "https://kumacdn.club/images/k/kanojo-okarishimasu/chapter-321/2-64e1d5a8.jpg",
+    "https://kumacdn.club/images/k/kanojo-okarishimasu/chapter-321/3-64e1d5a9.webp",
+];
+
+/// The series and chapter 321 fixtures, with `images` as the pages of the chapter
+#[cfg(any(feature = "cbz", feature = "epub", feature = "pdf"))]
+fn export_transport(images: [&[u8]; 3]) -> MemoryTransport {
+    let transport = MemoryTransport::new()
+        .with_html(
+            url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+            fixture!("manga_details"),
+        )
+        .with_html(
+            url("https://rawkuma.com/kanojo-okarishimasu-chapter-321/"),
+            fixture!("chapter"),
+        );
+    for (image, data) in CHAPTER_IMAGES.into_iter().zip(images) {
+        transport.insert(
+            url(image),
+            TransportResponse::new(url(image), StatusCode::OK, data),
+        );
+    }
+    transport
+}
+
+#[cfg(any(feature = "cbz", feature = "epub"))]
+fn export_pages(
+    formats: &[rawkuma_scraper::download::ImageFormat],
+) -> Vec<rawkuma_scraper::download::ChapterPage> {
+    formats
+        .iter()
+        .enumerate()
+        .map(|(index, format)| rawkuma_scraper::download::ChapterPage {
+            index,
+            url: url(CHAPTER_IMAGES[index]),
+            format: *format,
+            data: format!("page {}", index + 1).into_bytes(),
+        })
+        .collect()
+}
+
+#[cfg(feature = "cbz")]
+#[tokio::test]
+async fn cbz_archive() {
+    use std::io::{Cursor, Read};
+
+    use rawkuma_scraper::{
+        download::{ChapterDownloader, ImageFormat},
+        export::{
+            cbz::{write_cbz, CbzExporter},
+            ComicInfo,
+        },
+    };
+    use zip::{CompressionMethod, ZipArchive};
+
+    fn read_entries(archive: &[u8]) -> Vec<(String, CompressionMethod, Vec<u8>)> {
+        let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+        (0..archive.len())
+            .map(|index| {
+                let mut file = archive.by_index(index).unwrap();
+                let mut data = Vec::new();
+                file.read_to_end(&mut data).unwrap();
+                (file.name().to_string(), file.compression(), data)
+            })
+            .collect()
+    }
+
+    let details = parse_manga_details(
+        fixture!("manga_details"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    let chapter = &details.chapterlist.chapters[0];
+    let pages = export_pages(&[ImageFormat::Jpeg, ImageFormat::Png]);
+    let fetched_at = Utc.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap();
+    let comic_info = ComicInfo::new(&details.data, chapter, &fetched_at)
+        .with_language_iso("ja")
+        .with_page_count(pages.len());
+    let archive = write_cbz(Cursor::new(Vec::new()), &pages, &comic_info)
+        .unwrap()
+        .into_inner();
+    let entries = read_entries(&archive);
+    let names: Vec<&str> = entries.iter().map(|(name, _, _)| name.as_str()).collect();
+    assert_eq!(names, ["ComicInfo.xml", "001.jpg", "002.png"]);
+    assert_eq!(entries[1].1, CompressionMethod::Stored);
+    assert_eq!(entries[2].2, b"page 2");
+
+    let xml = String::from_utf8(entries[0].2.clone()).unwrap();
+    assert_eq!(xml, comic_info.to_xml());
+    for element in [
+        "<Title>Chapter 321</Title>",
+        "<Series>Kanojo, Okarishimasu</Series>",
+        "<Number>321</Number>",
+        "<Year>2024</Year>",
+        "<Month>3</Month>",
+        "<Day>3</Day>",
+        "<Genre>Comedy, Drama, Harem, Romance</Genre>",
+        "<Web>https://rawkuma.com/kanojo-okarishimasu-chapter-321/</Web>",
+        "<PageCount>2</PageCount>",
+        "<LanguageISO>ja</LanguageISO>",
+        "<Manga>YesAndRightToLeft</Manga>",
+        "<Page Image=\"0\" Type=\"FrontCover\" />",
+        "<Page Image=\"1\" />",
+    ] {
+        assert!(xml.contains(element), "{} not in {}", element, xml);
+    }
+    // The elements follow the xs:sequence of the ComicInfo v2.0 schema
+    let elements: Vec<&str> = xml
+        .lines()
+        .filter_map(|line| line.strip_prefix("  <"))
+        .filter(|line| !line.starts_with('/'))
+        .map(|line| line.split(['>', ' ']).next().unwrap())
+        .collect();
+    assert_eq!(
+        elements,
+        [
+            "Title",
+            "Series",
+            "Number",
+            "Summary",
+            "Year",
+            "Month",
+            "Day",
+            "Writer",
+            "Genre",
+            "Web",
+            "PageCount",
+            "LanguageISO",
+            "Manga",
+            "Pages",
+            "CommunityRating"
+        ]
+    );
+    assert!(ComicInfo::default()
+        .to_xml()
+        .ends_with("\">\n</ComicInfo>\n"));
+
+    // Relative dates resolve against the time the chapter list was fetched
+    let relative = parse_manga_details(
+        fixture!("relative_dates"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    let fetched_at = Utc.with_ymd_and_hms(2024, 3, 3, 1, 0, 0).unwrap();
+    let comic_info = ComicInfo::new(
+        &relative.data,
+        &relative.chapterlist.chapters[0],
+        &fetched_at,
+    );
+    assert_eq!(
+        (comic_info.year, comic_info.month, comic_info.day),
+        (Some(2024), Some(3), Some(2))
+    );
+    // The date parsed with the format of the source is used, not the displayed one
+    let mut dated = chapter.clone();
+    dated.chapter_date = "01.02.2024".to_string();
+    dated.date = ChapterDate::parse_with_format(&dated.chapter_date, Some("%d.%m.%Y"));
+    let comic_info = ComicInfo::new(&details.data, &dated, &fetched_at);
+    assert_eq!(
+        (comic_info.year, comic_info.month, comic_info.day),
+        (Some(2024), Some(2), Some(1))
+    );
+
+    // The language is the one of the source
+    let transport = export_transport([b"\xFF\xD8\xFF1", b"\xFF\xD8\xFF2", b"RIFF\0\0\0\0WEBP"]);
+    let mut config = sibling_config("example", "https://raw.example.org/");
+    config.language = "ko".to_string();
+    let client = RawKumaClient::with_transport(transport).with_source(config);
+    let exporter = CbzExporter::new(ChapterDownloader::new(client));
+    let archive = exporter
+        .export_to_vec(&details.data, chapter)
+        .await
+        .unwrap();
+    let entries = read_entries(&archive);
+    let names: Vec<&str> = entries.iter().map(|(name, _, _)| name.as_str()).collect();
+    assert_eq!(names, ["ComicInfo.xml", "001.jpg", "002.jpg", "003.webp"]);
+    let xml = String::from_utf8(entries[0].2.clone()).unwrap();
+    assert!(xml.contains("<LanguageISO>ko</LanguageISO>"));
+    assert!(xml.contains("<PageCount>3</PageCount>"));
+}
+
+/// Keeps the urls requested through `inner`.
+#[cfg(any(feature = "epub", feature = "pdf"))]
+struct LoggingTransport {
+    inner: MemoryTransport,
+    requests: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(any(feature = "epub", feature = "pdf"))]
+#[async_trait::async_trait]
+impl Transport for LoggingTransport {
+    async fn get(&self, url: Url, headers: HeaderMap) -> RawKumaResult<TransportResponse> {
+        self.requests.lock().unwrap().push(url.to_string());
+        self.inner.get(url, headers).await
+    }
+}
+
+#[cfg(feature = "epub")]
+#[tokio::test]
+async fn epub_book() {
+    use std::io::{Cursor, Read};
+
+    use rawkuma_scraper::{
+        download::{ChapterDownloader, ChapterPage, ImageFormat},
+        export::{
+            epub::{write_epub, EpubExporter},
+            ExportChapter,
+        },
+    };
+    use zip::{CompressionMethod, ZipArchive};
+
+    let details = parse_manga_details(
+        fixture!("manga_details"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    let chapters: Vec<ExportChapter> = details
+        .chapterlist
+        .get_range(320.5..=321.0)
+        .into_iter()
+        .map(|chapter| ExportChapter {
+            chapter,
+            pages: export_pages(&[ImageFormat::Jpeg, ImageFormat::Webp]),
+        })
+        .collect();
+    let cover = ChapterPage {
+        index: 0,
+        url: details.data.image.clone(),
+        format: ImageFormat::Png,
+        data: b"cover".to_vec(),
+    };
+    let book = write_epub(
+        Cursor::new(Vec::new()),
+        &details.data,
+        "ja",
+        Some(&cover),
+        &chapters,
+    )
+    .unwrap()
+    .into_inner();
+
+    let mut archive = ZipArchive::new(Cursor::new(book)).unwrap();
+    let mut read = |name: &str| {
+        let mut data = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut data)
+            .unwrap();
+        data
+    };
+    let opf = read("OEBPS/content.opf");
+    let nav = read("OEBPS/nav.xhtml");
+    let page = read("OEBPS/images/c0002_p0002.webp");
+    assert_eq!(read("mimetype"), "application/epub+zip");
+    let mimetype = archive.by_index(0).unwrap();
+    assert_eq!(mimetype.name(), "mimetype");
+    assert_eq!(mimetype.compression(), CompressionMethod::Stored);
+
+    assert!(opf.contains(
+        r#"<dc:identifier id="book-id">urn:rawkuma:kanojo-okarishimasu:320.5-321</dc:identifier>"#
+    ));
+    assert!(opf.contains("<dc:title>Kanojo, Okarishimasu</dc:title>"));
+    assert!(opf.contains("<dc:language>ja</dc:language>"));
+    assert!(opf.contains(r#"<meta property="rendition:layout">pre-paginated</meta>"#));
+    assert!(opf.contains(
+        r#"<item id="cover-image" href="images/cover.png" media-type="image/png" properties="cover-image"/>"#
+    ));
+    let spine = &opf[opf.find("<spine").unwrap()..opf.find("</spine>").unwrap()];
+    let itemrefs: Vec<&str> = spine
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(r#"<itemref idref=""#))
+        .map(|line| line.trim_end_matches(r#""/>"#))
+        .collect();
+    assert_eq!(
+        itemrefs,
+        [
+            "cover",
+            "page_c0001_p0001",
+            "page_c0001_p0002",
+            "page_c0002_p0001",
+            "page_c0002_p0002"
+        ]
+    );
+    assert!(spine.contains(r#"page-progression-direction="rtl""#));
+    assert!(nav.contains(r#"<li><a href="xhtml/c0001_p0001.xhtml">Chapter 320.5</a></li>"#));
+    assert!(nav.contains(r#"<li><a href="xhtml/c0002_p0001.xhtml">Chapter 321</a></li>"#));
+    assert_eq!(page, "page 2");
+
+    // Without any page, the navigation list would be empty
+    let empty: Vec<ExportChapter> = chapters
+        .iter()
+        .map(|chapter| ExportChapter {
+            chapter: chapter.chapter.clone(),
+            pages: Vec::new(),
+        })
+        .collect();
+    assert!(matches!(
+        write_epub(Cursor::new(Vec::new()), &details.data, "ja", None, &empty),
+        Err(Error::NoChapters)
+    ));
+
+    // An empty range fails before anything is downloaded
+    let transport = Arc::new(LoggingTransport {
+        inner: export_transport([b"\xFF\xD8\xFF1", b"\xFF\xD8\xFF2", b"RIFF\0\0\0\0WEBP"]),
+        requests: Default::default(),
+    });
+    let client = RawKumaClientBuilder::default()
+        .transport(transport.clone() as Arc<dyn Transport>)
+        .build()
+        .unwrap();
+    let exporter = EpubExporter::new(ChapterDownloader::new(client));
+    assert!(matches!(
+        exporter.export_to_vec(&details, 500.0..).await,
+        Err(Error::NoChapters)
+    ));
+    assert!(transport.requests.lock().unwrap().is_empty());
+
+    // A missing cover falls back to the first page
+    let book = exporter
+        .export_to_vec(&details, 321.0..=321.0)
+        .await
+        .unwrap();
+    let requests = transport.requests.lock().unwrap().clone();
+    assert_eq!(requests[0], details.data.image.to_string());
+    let mut archive = ZipArchive::new(Cursor::new(book)).unwrap();
+    let mut cover = Vec::new();
+    archive
+        .by_name("OEBPS/images/cover.jpg")
+        .unwrap()
+        .read_to_end(&mut cover)
+        .unwrap();
+    assert_eq!(cover, b"\xFF\xD8\xFF1");
+    let mut opf = String::new();
+    archive
+        .by_name("OEBPS/content.opf")
+        .unwrap()
+        .read_to_string(&mut opf)
+        .unwrap();
+    let spine = &opf[opf.find("<spine").unwrap()..];
+    assert!(spine.find(r#""cover""#).unwrap() < spine.find(r#""page_c0001_p0001""#).unwrap());
+
+    // The file is written chapter by chapter, and removed when the export fails
+    let path = std::env::temp_dir().join("rawkuma-scraper-export.epub");
+    exporter
+        .export(&details, 321.0..=321.0, &path)
+        .await
+        .unwrap();
+    assert!(ZipArchive::new(std::fs::File::open(&path).unwrap())
+        .unwrap()
+        .by_name("OEBPS/content.opf")
+        .is_ok());
+    assert!(exporter
+        .export(&details, 320.5..=320.5, &path)
+        .await
+        .is_err());
+    assert!(!path.exists());
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn pdf_document() {
+    use std::io::Cursor;
+
+    use lopdf::{Dictionary, Document, Object};
+    use rawkuma_scraper::{
+        download::{ChapterPage, ImageFormat},
+        export::{pdf::write_pdf, ExportChapter},
+    };
+
+    fn encode(width: u32, height: u32, format: image::ImageFormat) -> Vec<u8> {
+        let mut data = Cursor::new(Vec::new());
+        image::RgbImage::from_pixel(width, height, image::Rgb([200, 10, 10]))
+            .write_to(&mut data, format)
+            .unwrap();
+        data.into_inner()
+    }
+    fn page(index: usize, format: ImageFormat, data: Vec<u8>) -> ChapterPage {
+        ChapterPage {
+            index,
+            url: url(CHAPTER_IMAGES[index]),
+            format,
+            data,
+        }
+    }
+    fn text(object: &Object) -> String {
+        match object {
+            Object::String(bytes, _) => String::from_utf8(bytes.clone()).unwrap(),
+            _ => panic!("not a string: {:?}", object),
+        }
+    }
+    fn dictionary<'a>(document: &'a Document, object: &Object) -> &'a Dictionary {
+        document
+            .get_dictionary(object.as_reference().unwrap())
+            .unwrap()
+    }
+
+    let details = parse_manga_details(
+        fixture!("manga_details"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    let mut chapters = details.chapterlist.get_range(320.5..=321.0).into_iter();
+    let jpeg = encode(16, 24, image::ImageFormat::Jpeg);
+    let chapters = vec![
+        ExportChapter {
+            chapter: chapters.next().unwrap(),
+            pages: vec![page(0, ImageFormat::Jpeg, jpeg.clone())],
+        },
+        ExportChapter {
+            chapter: chapters.next().unwrap(),
+            pages: vec![
+                page(0, ImageFormat::Png, encode(30, 20, image::ImageFormat::Png)),
+                page(1, ImageFormat::Jpeg, jpeg.clone()),
+            ],
+        },
+    ];
+    let document = write_pdf(Vec::new(), &details.data, &chapters).unwrap();
+    let document = Document::load_mem(&document).unwrap();
+
+    let pages = document.get_pages();
+    assert_eq!(pages.len(), 3);
+    let image = |page: u32| {
+        let page = document.get_dictionary(pages[&page]).unwrap();
+        let resources = page.get(b"Resources").unwrap().as_dict().unwrap();
+        let xobjects = resources.get(b"XObject").unwrap().as_dict().unwrap();
+        let id = xobjects.get(b"Im0").unwrap().as_reference().unwrap();
+        let stream = document
+            .get_object(id)
+            .unwrap()
+            .as_stream()
+            .unwrap()
+            .clone();
+        let media_box = page.get(b"MediaBox").unwrap().as_array().unwrap().clone();
+        (stream, media_box)
+    };
+
+    // JPEG pages are embedded as is
+    let (stream, media_box) = image(1);
+    assert_eq!(
+        stream.dict.get(b"Filter").unwrap().as_name().unwrap(),
+        b"DCTDecode"
+    );
+    assert_eq!(stream.content, jpeg);
+    assert!(stream.dict.get(b"Decode").is_err());
+    assert_eq!(media_box[2].as_float().unwrap(), 16.0);
+    assert_eq!(media_box[3].as_float().unwrap(), 24.0);
+
+    // Other formats are decoded to RGB
+    let (stream, media_box) = image(2);
+    assert_eq!(
+        stream.dict.get(b"Filter").unwrap().as_name().unwrap(),
+        b"FlateDecode"
+    );
+    assert_eq!(stream.dict.get(b"Width").unwrap().as_i64().unwrap(), 30);
+    assert_eq!(stream.dict.get(b"Height").unwrap().as_i64().unwrap(), 20);
+    assert_eq!(
+        stream.dict.get(b"ColorSpace").unwrap().as_name().unwrap(),
+        b"DeviceRGB"
+    );
+    // lopdf only decompresses the streams that are not images
+    let mut pixels = stream.clone();
+    pixels.dict.remove(b"Subtype");
+    assert_eq!(
+        pixels.decompressed_content().unwrap(),
+        [200, 10, 10].repeat(30 * 20)
+    );
+    assert_eq!(media_box[2].as_float().unwrap(), 30.0);
+
+    // One bookmark per chapter, on its first page
+    let catalog = document.catalog().unwrap();
+    assert_eq!(
+        catalog.get(b"PageMode").unwrap().as_name().unwrap(),
+        b"UseOutlines"
+    );
+    let outlines = dictionary(&document, catalog.get(b"Outlines").unwrap());
+    assert_eq!(outlines.get(b"Count").unwrap().as_i64().unwrap(), 2);
+    let mut item = Some(dictionary(&document, outlines.get(b"First").unwrap()));
+    let mut bookmarks: Vec<(String, u32)> = Vec::new();
+    while let Some(current) = item {
+        let destination = current.get(b"Dest").unwrap().as_array().unwrap();
+        let page_id = destination[0].as_reference().unwrap();
+        let (number, _) = pages.iter().find(|(_, id)| **id == page_id).unwrap();
+        bookmarks.push((text(current.get(b"Title").unwrap()), *number));
+        item = current
+            .get(b"Next")
+            .ok()
+            .map(|next| dictionary(&document, next));
+    }
+    assert_eq!(
+        bookmarks,
+        [
+            ("Chapter 320.5".to_string(), 1),
+            ("Chapter 321".to_string(), 2)
+        ]
+    );
+    let info = dictionary(&document, document.trailer.get(b"Info").unwrap());
+    assert_eq!(text(info.get(b"Title").unwrap()), "Kanojo, Okarishimasu");
+
+    // AVIF can't be decoded without a native library
+    let avif = vec![ExportChapter {
+        chapter: chapters[0].chapter.clone(),
+        pages: vec![page(0, ImageFormat::Avif, b"\0\0\0\x1cftypavif".to_vec())],
+    }];
+    assert!(matches!(
+        write_pdf(Vec::new(), &details.data, &avif),
+        Err(Error::UnsupportedImageFormat { url: image, mime_type })
+            if image == url(CHAPTER_IMAGES[0]) && mime_type == "image/avif"
+    ));
+
+    // Long webtoon strips are scaled down to the largest page size
+    let strip = vec![ExportChapter {
+        chapter: chapters[0].chapter.clone(),
+        pages: vec![page(
+            0,
+            ImageFormat::Jpeg,
+            encode(8, 28800, image::ImageFormat::Jpeg),
+        )],
+    }];
+    let document = write_pdf(Vec::new(), &details.data, &strip).unwrap();
+    let document = Document::load_mem(&document).unwrap();
+    let page_id = document.get_pages()[&1];
+    let media_box = document
+        .get_dictionary(page_id)
+        .unwrap()
+        .get(b"MediaBox")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .clone();
+    assert_eq!(media_box[2].as_float().unwrap(), 4.0);
+    assert_eq!(media_box[3].as_float().unwrap(), 14400.0);
+
+    // Adobe CMYK JPEGs store inverted samples
+    let mut cmyk: Vec<u8> = vec![0xFF, 0xD8, 0xFF, 0xEE, 0x00, 0x0E];
+    cmyk.extend_from_slice(b"Adobe\0\x64\0\0\0\0\x02");
+    cmyk.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x14, 0x08, 0x00, 0x10, 0x00, 0x10, 0x04]);
+    cmyk.extend_from_slice(&[1, 0x11, 0, 2, 0x11, 0, 3, 0x11, 0, 4, 0x11, 0]);
+    cmyk.extend_from_slice(&[0xFF, 0xD9]);
+    let cmyk = vec![ExportChapter {
+        chapter: chapters[0].chapter.clone(),
+        pages: vec![page(0, ImageFormat::Jpeg, cmyk)],
+    }];
+    let document = write_pdf(Vec::new(), &details.data, &cmyk).unwrap();
+    let document = Document::load_mem(&document).unwrap();
+    let stream = document
+        .objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .find(|stream| stream.dict.has(b"Width"))
+        .unwrap();
+    assert_eq!(
+        stream.dict.get(b"ColorSpace").unwrap().as_name().unwrap(),
+        b"DeviceCMYK"
+    );
+    let decode: Vec<i64> = stream
+        .dict
+        .get(b"Decode")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value.as_i64().unwrap())
+        .collect();
+    assert_eq!(decode, [1, 0, 1, 0, 1, 0, 1, 0]);
+}
+
+#[cfg(feature = "pdf")]
+#[tokio::test]
+async fn pdf_exporter() {
+    use std::io::Cursor;
+
+    use lopdf::Document;
+    use rawkuma_scraper::{
+        download::ChapterDownloader,
+        export::{
+            pdf::{write_pdf, PdfExporter},
+            ExportChapter,
+        },
+    };
+
+    let details = parse_manga_details(
+        fixture!("manga_details"),
+        &url("https://rawkuma.com/manga/kanojo-okarishimasu/"),
+    )
+    .unwrap();
+    // Without any page, the document would be empty
+    let empty: Vec<ExportChapter> = details
+        .chapterlist
+        .get_range(320.5..=321.0)
+        .into_iter()
+        .map(|chapter| ExportChapter {
+            chapter,
+            pages: Vec::new(),
+        })
+        .collect();
+    assert!(matches!(
+        write_pdf(Vec::new(), &details.data, &empty),
+        Err(Error::NoChapters)
+    ));
+    assert!(matches!(
+        write_pdf(Vec::new(), &details.data, &[]),
+        Err(Error::NoChapters)
+    ));
+
+    // An empty range fails before anything is downloaded
+    let mut jpeg = Cursor::new(Vec::new());
+    image::RgbImage::from_pixel(16, 24, image::Rgb([200, 10, 10]))
+        .write_to(&mut jpeg, image::ImageFormat::Jpeg)
+        .unwrap();
+    let jpeg = jpeg.into_inner();
+    let transport = Arc::new(LoggingTransport {
+        inner: export_transport([&jpeg, &jpeg, &jpeg]),
+        requests: Default::default(),
+    });
+    let client = RawKumaClientBuilder::default()
+        .transport(transport.clone() as Arc<dyn Transport>)
+        .build()
+        .unwrap();
+    let exporter = PdfExporter::new(ChapterDownloader::new(client));
+    assert!(matches!(
+        exporter.export_to_vec(&details, 500.0..).await,
+        Err(Error::NoChapters)
+    ));
+    assert!(transport.requests.lock().unwrap().is_empty());
+
+    // The file is written chapter by chapter, and removed when the export fails
+    let path = std::env::temp_dir().join("rawkuma-scraper-export.pdf");
+    exporter
+        .export(&details, 321.0..=321.0, &path)
+        .await
+        .unwrap();
+    let document = Document::load(&path).unwrap();
+    assert_eq!(document.get_pages().len(), 3);
+    assert!(exporter
+        .export(&details, 320.5..=320.5, &path)
+        .await
+        .is_err());
+    assert!(!path.exists());
+}